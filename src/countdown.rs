@@ -0,0 +1,275 @@
+use crate::core::MessageHandle;
+use crate::error::Result;
+use crate::menu::get_listeners_from_context;
+use crate::menu::traits::EventDrivenMessage;
+use futures::future::BoxFuture;
+use serenity::async_trait;
+use serenity::builder::CreateMessage;
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use serenity::utils::Colour;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+/// The default format of a running countdown
+pub static DEFAULT_COUNTDOWN_FORMAT: &str = "⏳ {remaining} remaining";
+/// The default text of a finished countdown
+pub static DEFAULT_FINISHED_TEXT: &str = "⌛ Finished";
+
+pub type CountdownCallbackArc =
+    Arc<dyn for<'b> Fn(&'b Http) -> BoxFuture<'b, Result<()>> + Send + Sync>;
+
+/// A message that displays the remaining time until a point in time
+/// and is updated periodically
+pub struct CountdownMessage {
+    message: MessageHandle,
+    title: Option<String>,
+    format: String,
+    finished_text: String,
+    colour: Option<Colour>,
+    ends_at: SystemTime,
+    ping: Option<String>,
+    on_finish: Option<CountdownCallbackArc>,
+    displayed_text: String,
+    finished: bool,
+}
+
+impl CountdownMessage {
+    /// Returns the remaining time of the countdown
+    pub fn remaining(&self) -> Duration {
+        self.ends_at
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+
+    /// Returns the text that is displayed for the current state
+    fn text(&self) -> String {
+        if self.finished {
+            self.finished_text.clone()
+        } else {
+            self.format
+                .replace("{remaining}", &format_duration(self.remaining()))
+        }
+    }
+
+    fn render(&self, text: String) -> CreateMessage<'static> {
+        let mut message = CreateMessage::default();
+        message.embed(|e| {
+            if let Some(title) = &self.title {
+                e.title(title);
+            }
+            if let Some(colour) = self.colour {
+                e.colour(colour);
+            }
+            e.description(text)
+        });
+
+        message
+    }
+
+    /// Displays the current state if it changed
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn display(&mut self, http: &Http) -> Result<()> {
+        let text = self.text();
+        if text == self.displayed_text {
+            return Ok(());
+        }
+        self.displayed_text = text.clone();
+        http.edit_message(
+            self.message.channel_id,
+            self.message.message_id,
+            &serde_json::to_value(self.render(text).0).unwrap(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Shows the finished state, posts the ping and runs the finish callback
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn finish(&mut self, http: &Http) -> Result<()> {
+        self.finished = true;
+
+        // a failed edit must not prevent the ping and the callback
+        if let Err(e) = self.display(http).await {
+            tracing::error!("Failed to display finished countdown: {:?}", e);
+        }
+        if let Some(ping) = &self.ping {
+            let mut message = CreateMessage::default();
+            message.content(ping);
+
+            if let Err(e) = http
+                .send_message(
+                    self.message.channel_id,
+                    &serde_json::to_value(message.0).unwrap(),
+                )
+                .await
+            {
+                tracing::error!("Failed to send countdown ping: {:?}", e);
+            }
+        }
+        if let Some(on_finish) = self.on_finish.clone() {
+            tracing::debug!("Running finish callback");
+            on_finish(http).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventDrivenMessage for CountdownMessage {
+    fn is_frozen(&self) -> bool {
+        self.finished
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        if SystemTime::now() >= self.ends_at {
+            tracing::debug!("Countdown finished");
+            self.finish(http).await
+        } else {
+            self.display(http).await
+        }
+    }
+}
+
+/// A builder for countdown messages
+pub struct CountdownBuilder {
+    ends_at: SystemTime,
+    title: Option<String>,
+    format: String,
+    finished_text: String,
+    colour: Option<Colour>,
+    ping: Option<String>,
+    on_finish: Option<CountdownCallbackArc>,
+}
+
+impl CountdownBuilder {
+    /// Creates a countdown that ends after the given duration
+    pub fn new(duration: Duration) -> Self {
+        Self::until(SystemTime::now() + duration)
+    }
+
+    /// Creates a countdown that ends at the given time
+    pub fn until(ends_at: SystemTime) -> Self {
+        Self {
+            ends_at,
+            title: None,
+            format: DEFAULT_COUNTDOWN_FORMAT.to_string(),
+            finished_text: DEFAULT_FINISHED_TEXT.to_string(),
+            colour: None,
+            ping: None,
+            on_finish: None,
+        }
+    }
+
+    /// Sets the title of the countdown embed
+    pub fn title<S: ToString>(mut self, title: S) -> Self {
+        self.title = Some(title.to_string());
+
+        self
+    }
+
+    /// Sets the text of the running countdown.
+    /// `{remaining}` is replaced with the remaining time
+    pub fn format<S: ToString>(mut self, format: S) -> Self {
+        self.format = format.to_string();
+
+        self
+    }
+
+    /// Sets the text of the finished countdown
+    pub fn finished_text<S: ToString>(mut self, text: S) -> Self {
+        self.finished_text = text.to_string();
+
+        self
+    }
+
+    /// Sets the colour of the countdown embed
+    pub fn colour<C: Into<Colour>>(mut self, colour: C) -> Self {
+        self.colour = Some(colour.into());
+
+        self
+    }
+
+    /// Posts a message with the given content when the countdown has finished.
+    /// Can be used to mention users or roles
+    pub fn ping<S: ToString>(mut self, content: S) -> Self {
+        self.ping = Some(content.to_string());
+
+        self
+    }
+
+    /// Sets a callback that is run when the countdown has finished
+    pub fn on_finish<F>(mut self, callback: F) -> Self
+    where
+        F: for<'b> Fn(&'b Http) -> BoxFuture<'b, Result<()>> + Send + Sync + 'static,
+    {
+        self.on_finish = Some(Arc::new(callback));
+
+        self
+    }
+
+    /// Creates the countdown message in the given channel.
+    /// The message is updated by the update loop
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build(self, ctx: &Context, channel_id: ChannelId) -> Result<MessageHandle> {
+        let mut countdown = CountdownMessage {
+            message: MessageHandle::from_raw_ids(channel_id.0, 0),
+            title: self.title,
+            format: self.format,
+            finished_text: self.finished_text,
+            colour: self.colour,
+            ends_at: self.ends_at,
+            ping: self.ping,
+            on_finish: self.on_finish,
+            displayed_text: String::new(),
+            finished: false,
+        };
+        countdown.displayed_text = countdown.text();
+        let mut page = countdown.render(countdown.displayed_text.clone());
+
+        let message = channel_id
+            .send_message(ctx, |msg| {
+                mem::swap(msg, &mut page);
+                msg
+            })
+            .await?;
+        let handle = MessageHandle::new(message.channel_id, message.id);
+        countdown.message = handle;
+
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.insert(handle, Arc::new(Mutex::new(Box::new(countdown).into())));
+
+        Ok(handle)
+    }
+}
+
+/// Formats the duration with the two most significant units.
+/// Seconds are only displayed for durations below one hour
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86400,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+    );
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -20,7 +20,7 @@ impl EphemeralMessage {
     ) -> Result<()> {
         tracing::debug!("Creating ephemeral message from existing message");
         let handle = MessageHandle::new(message.channel_id, message.id);
-        let http = Arc::clone(&http);
+        let http = Arc::clone(http);
 
         tracing::debug!("Starting delete task");
         tokio::spawn(async move {
@@ -1,9 +1,10 @@
 use crate::core::MessageHandle;
-use crate::menu::get_listeners_from_context;
+use crate::menu::{get_listeners_from_context, MessageRef};
 use crate::Result;
 use serenity::client::Context;
-use serenity::model::channel::Reaction;
+use serenity::model::channel::{Message, Reaction};
 use serenity::model::id::{ChannelId, MessageId};
+use serenity::model::interactions::Interaction;
 use std::sync::Arc;
 use tokio::time::Duration;
 
@@ -22,14 +23,20 @@ pub async fn start_update_loop(ctx: &Context) -> Result<()> {
             {
                 tracing::trace!("Updating messages...");
                 let mut frozen_messages = Vec::new();
-
-                for (key, value) in event_messages
+                let mut updated_messages: Vec<MessageRef> = Vec::new();
+                let messages = event_messages
                     .iter()
-                    .map(|e| (e.key().clone(), e.value().clone()))
-                {
+                    .map(|e| (*e.key(), e.value().clone()))
+                    .collect::<Vec<_>>();
+
+                for (key, value) in messages {
                     let mut msg = value.lock().await;
-                    if let Err(e) = msg.update(&http).await {
-                        tracing::error!("Failed to update message: {:?}", e);
+                    // a message can be registered with multiple handles
+                    if !updated_messages.iter().any(|m| Arc::ptr_eq(m, &value)) {
+                        if let Err(e) = msg.update(&http).await {
+                            tracing::error!("Failed to update message: {:?}", e);
+                        }
+                        updated_messages.push(Arc::clone(&value));
                     }
                     if msg.is_frozen() {
                         frozen_messages.push(key);
@@ -47,6 +54,27 @@ pub async fn start_update_loop(ctx: &Context) -> Result<()> {
     Ok(())
 }
 
+/// To be called before the client shuts down.
+/// Notifies all event driven messages and stops handling their events
+#[tracing::instrument(level = "debug", skip(ctx))]
+pub async fn handle_shutdown(ctx: &Context) -> Result<()> {
+    let listeners = get_listeners_from_context(ctx).await?;
+    let affected_messages = listeners
+        .iter()
+        .map(|e| e.value().clone())
+        .collect::<Vec<_>>();
+    listeners.clear();
+
+    for msg in affected_messages {
+        let mut msg = msg.lock().await;
+        if let Err(e) = msg.on_shutdown(ctx).await {
+            tracing::error!("Failed to shut down message: {:?}", e);
+        }
+    }
+
+    Ok(())
+}
+
 /// To be fired from the serenity handler when a message was deleted
 #[tracing::instrument(level = "debug", skip(ctx))]
 pub async fn handle_message_delete(
@@ -58,9 +86,8 @@ pub async fn handle_message_delete(
     let listeners = get_listeners_from_context(ctx).await?;
     let handle = MessageHandle::new(channel_id, message_id);
 
-    if let Some(msg) = listeners.get(&handle) {
-        affected_messages.push(msg.value().clone());
-        listeners.remove(&handle);
+    if let Some((_, msg)) = listeners.remove(&handle) {
+        affected_messages.push(msg);
     }
 
     for msg in affected_messages {
@@ -84,9 +111,8 @@ pub async fn handle_message_delete_bulk(
 
     for message_id in message_ids {
         let handle = MessageHandle::new(channel_id, *message_id);
-        if let Some(msg) = listeners.get(&handle) {
-            affected_messages.push(msg.value().clone());
-            listeners.remove(&handle);
+        if let Some((_, msg)) = listeners.remove(&handle) {
+            affected_messages.push(msg);
         }
     }
 
@@ -117,7 +143,7 @@ pub async fn handle_reaction_add(ctx: &Context, reaction: &Reaction) -> Result<(
     Ok(())
 }
 
-/// Fired when a reaction was added to a message
+/// Fired when a reaction was removed from a message
 #[tracing::instrument(level = "debug", skip(ctx))]
 pub async fn handle_reaction_remove(ctx: &Context, reaction: &Reaction) -> Result<()> {
     let listeners = get_listeners_from_context(ctx).await?;
@@ -135,3 +161,53 @@ pub async fn handle_reaction_remove(ctx: &Context, reaction: &Reaction) -> Resul
 
     Ok(())
 }
+
+/// Fired when a message was created.
+/// Notifies every message in the same channel
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn handle_message_create(ctx: &Context, message: &Message) -> Result<()> {
+    let listeners = get_listeners_from_context(ctx).await?;
+
+    let mut affected_messages: Vec<MessageRef> = Vec::new();
+    for entry in listeners.iter() {
+        // a message can be registered with multiple handles
+        if entry.key().channel_id == message.channel_id.0
+            && !affected_messages
+                .iter()
+                .any(|m| Arc::ptr_eq(m, entry.value()))
+        {
+            affected_messages.push(entry.value().clone());
+        }
+    }
+
+    for msg in affected_messages {
+        let mut msg = msg.lock().await;
+        msg.on_message_create(ctx, message.clone()).await?;
+    }
+
+    Ok(())
+}
+
+/// Fired when an interaction was created
+#[tracing::instrument(level = "debug", skip(ctx))]
+pub async fn handle_interaction_create(ctx: &Context, interaction: &Interaction) -> Result<()> {
+    let component = if let Interaction::MessageComponent(component) = interaction {
+        component
+    } else {
+        return Ok(());
+    };
+    let listeners = get_listeners_from_context(ctx).await?;
+    let handle = MessageHandle::new(component.channel_id, component.message.id);
+
+    let mut affected_messages = Vec::new();
+    if let Some(msg) = listeners.get(&handle) {
+        affected_messages.push(msg.value().clone());
+    }
+
+    for msg in affected_messages {
+        let mut msg = msg.lock().await;
+        msg.on_interaction_create(ctx, component.clone()).await?;
+    }
+
+    Ok(())
+}
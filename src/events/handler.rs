@@ -24,15 +24,13 @@ macro_rules! handle_events {
     }
 }
 
+pub type EventCallbackResult<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+pub type EventCallbackArc<T> =
+    Arc<dyn for<'a> Fn(&'a Context, &'a T) -> EventCallbackResult<'a> + Send + Sync>;
+
 pub struct EventCallback<T> {
-    inner: Arc<
-        dyn for<'a> Fn(
-                &'a Context,
-                &'a T,
-            ) -> Pin<Box<(dyn Future<Output = Result<()>> + Send + 'a)>>
-            + Send
-            + Sync,
-    >,
+    inner: EventCallbackArc<T>,
 }
 
 impl<T> EventCallback<T> {
@@ -83,14 +81,9 @@ impl RichEventHandler {
         }
     }
 
-    pub fn add_event<T: 'static, F: 'static>(&mut self, cb: F) -> &mut Self
+    pub fn add_event<T: 'static, F>(&mut self, cb: F) -> &mut Self
     where
-        F: for<'a> Fn(
-                &'a Context,
-                &'a T,
-            ) -> Pin<Box<(dyn Future<Output = Result<()>> + Send + 'a)>>
-            + Send
-            + Sync,
+        F: for<'a> Fn(&'a Context, &'a T) -> EventCallbackResult<'a> + Send + Sync + 'static,
     {
         let type_id = TypeId::of::<T>();
         let callbacks = if let Some(cbs) = self.callbacks.get_mut(&type_id) {
@@ -122,6 +115,9 @@ impl Default for RichEventHandler {
             .add_event(|ctx, e: &event::ReactionRemoveEvent| {
                 Box::pin(event_callbacks::handle_reaction_remove(ctx, &e.reaction))
             })
+            .add_event(|ctx, e: &event::MessageCreateEvent| {
+                Box::pin(event_callbacks::handle_message_create(ctx, &e.message))
+            })
             .add_event(|ctx, e: &event::MessageDeleteEvent| {
                 Box::pin(event_callbacks::handle_message_delete(
                     ctx,
@@ -135,6 +131,12 @@ impl Default for RichEventHandler {
                     e.channel_id,
                     &e.ids,
                 ))
+            })
+            .add_event(|ctx, e: &event::InteractionCreateEvent| {
+                Box::pin(event_callbacks::handle_interaction_create(
+                    ctx,
+                    &e.interaction,
+                ))
             });
 
         handler
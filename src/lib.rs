@@ -1,8 +1,15 @@
 pub mod core;
+pub mod countdown;
 pub mod ephemeral_message;
 mod error;
 pub mod events;
+pub mod live_log;
 pub mod menu;
+pub mod poll;
+pub mod progress;
+pub mod reaction_roles;
+pub mod sticky;
+pub mod wizard;
 
 pub static VERSION: &str = env!("CARGO_PKG_VERSION");
 pub use crate::core::RegisterAdditions;
@@ -0,0 +1,441 @@
+use crate::core::MessageHandle;
+use crate::error::Result;
+use crate::menu::text::MAX_CONTENT_LENGTH;
+use crate::menu::traits::EventDrivenMessage;
+use crate::menu::{get_listeners_from_context, EventDrivenMessagesRef};
+use serde_json::json;
+use serenity::async_trait;
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+
+/// The default minimum time between two edits of a live log message
+pub static DEFAULT_LOG_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Displayed in the code block while the log has no lines
+static EMPTY_LOG_PLACEHOLDER: &str = "…";
+
+struct LogState {
+    pending: Vec<String>,
+    flush_pending: bool,
+    last_edit: Instant,
+    closed: bool,
+}
+
+/// The messages of the log and the lines of the current message
+struct LogMessages {
+    handles: Vec<MessageHandle>,
+    lines: Vec<String>,
+}
+
+/// The state shared between a live log message and its handles
+struct LogShared {
+    language: String,
+    max_length: usize,
+    min_interval: Duration,
+    listeners: EventDrivenMessagesRef,
+    state: std::sync::Mutex<LogState>,
+    /// Serializes edits so that lines are displayed in order
+    messages: Mutex<LogMessages>,
+}
+
+/// Renders the lines as a code block with the given language
+fn render_lines(language: &str, lines: &[String]) -> String {
+    let content = if lines.is_empty() {
+        EMPTY_LOG_PLACEHOLDER.to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    format!("```{}\n{}\n```", language, content)
+}
+
+/// Returns the number of characters of the rendered code block.
+/// Expects at least one line
+fn rendered_length(language: &str, lines: &[String]) -> usize {
+    let content_length = lines.iter().map(|l| l.chars().count() + 1).sum::<usize>();
+
+    language.chars().count() + content_length + 7
+}
+
+/// Splits the line into pieces that fit into a message on their own
+fn split_line(language: &str, line: &str, max_length: usize) -> Vec<String> {
+    let max_line_length = max_length
+        .saturating_sub(rendered_length(language, &[String::new()]))
+        .max(1);
+    let chars = line.chars().collect::<Vec<char>>();
+
+    if chars.is_empty() {
+        vec![String::new()]
+    } else {
+        chars
+            .chunks(max_line_length)
+            .map(|c| c.iter().collect::<String>())
+            .collect()
+    }
+}
+
+impl LogShared {
+    fn render(&self, lines: &[String]) -> String {
+        render_lines(&self.language, lines)
+    }
+
+    fn rendered_length(&self, lines: &[String]) -> usize {
+        rendered_length(&self.language, lines)
+    }
+
+    /// Displays all pending lines. Continues the log in a new message
+    /// when the current message would exceed the maximum length.
+    /// Lines that couldn't be displayed are queued again
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn flush(&self, http: &Http) -> Result<()> {
+        let mut messages = self.messages.lock().await;
+        let pending = {
+            let mut state = self.state.lock().unwrap();
+            state.flush_pending = false;
+            state.last_edit = Instant::now();
+            std::mem::take(&mut state.pending)
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let pieces = pending
+            .iter()
+            .flat_map(|line| split_line(&self.language, line, self.max_length))
+            .collect::<Vec<String>>();
+        let mut displayed = 0;
+        let result = self
+            .display_pieces(http, &mut messages, &pieces, &mut displayed)
+            .await;
+
+        if result.is_err() {
+            let mut state = self.state.lock().unwrap();
+
+            if !state.closed {
+                let remaining = pieces[displayed..].to_vec();
+                state.pending.splice(0..0, remaining);
+            }
+        }
+
+        result
+    }
+
+    /// Displays the pieces and counts the pieces that are displayed.
+    /// The lines of the current message only change after they were displayed
+    async fn display_pieces(
+        &self,
+        http: &Http,
+        messages: &mut LogMessages,
+        pieces: &[String],
+        displayed: &mut usize,
+    ) -> Result<()> {
+        let mut lines = messages.lines.clone();
+
+        for (i, piece) in pieces.iter().enumerate() {
+            lines.push(piece.clone());
+
+            if lines.len() > 1 && self.rendered_length(&lines) > self.max_length {
+                lines.pop();
+                if lines.len() > messages.lines.len() {
+                    self.edit_current(http, messages, &lines).await?;
+                    messages.lines = lines;
+                    *displayed = i;
+                }
+                lines = vec![piece.clone()];
+                self.roll_over(http, messages, &lines).await?;
+                messages.lines = lines.clone();
+                *displayed = i + 1;
+            }
+        }
+        if lines.len() > messages.lines.len() {
+            self.edit_current(http, messages, &lines).await?;
+            messages.lines = lines;
+            *displayed = pieces.len();
+        }
+
+        Ok(())
+    }
+
+    async fn edit_current(
+        &self,
+        http: &Http,
+        messages: &LogMessages,
+        lines: &[String],
+    ) -> Result<()> {
+        let current = messages.handles[messages.handles.len() - 1];
+        http.edit_message(
+            current.channel_id,
+            current.message_id,
+            &json!({ "content": self.render(lines) }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Creates a new message with the given lines and moves the listener to it
+    async fn roll_over(
+        &self,
+        http: &Http,
+        messages: &mut LogMessages,
+        lines: &[String],
+    ) -> Result<()> {
+        let previous = messages.handles[messages.handles.len() - 1];
+        let message = http
+            .send_message(
+                previous.channel_id,
+                &json!({ "content": self.render(lines) }),
+            )
+            .await?;
+        let handle = MessageHandle::new(message.channel_id, message.id);
+        tracing::debug!("Continuing log in new message {:?}", handle);
+        messages.handles.push(handle);
+
+        if let Some((_, log)) = self.listeners.remove(&previous) {
+            self.listeners.insert(handle, log);
+        }
+
+        Ok(())
+    }
+}
+
+/// A cloneable handle to append lines to a live log from background tasks.
+/// Lines are batched into periodic edits
+#[derive(Clone)]
+pub struct LiveLogHandle {
+    http: Arc<Http>,
+    runtime: Handle,
+    shared: Arc<LogShared>,
+}
+
+impl LiveLogHandle {
+    /// Appends the given text to the log. Multiline texts are split into lines.
+    /// Lines appended within the interval are displayed when it has passed.
+    /// Can be called from threads outside of the tokio runtime
+    pub fn append<S: ToString>(&self, text: S) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.closed {
+            return;
+        }
+        let text = text.to_string().replace("```", "`\u{200b}``");
+        state.pending.extend(text.lines().map(String::from));
+
+        if !state.flush_pending {
+            state.flush_pending = true;
+            let delay = self
+                .shared
+                .min_interval
+                .saturating_sub(state.last_edit.elapsed());
+            let handle = self.clone();
+
+            self.runtime.spawn(async move {
+                tokio::time::sleep(delay).await;
+
+                if let Err(e) = handle.flush().await {
+                    tracing::error!("Failed to update live log: {:?}", e);
+                }
+            });
+        }
+    }
+
+    /// Displays all pending lines immediately
+    pub async fn flush(&self) -> Result<()> {
+        self.shared.flush(&self.http).await
+    }
+
+    /// Displays all pending lines and stops accepting new lines
+    pub async fn finish(&self) -> Result<()> {
+        self.flush().await?;
+        self.shared.state.lock().unwrap().closed = true;
+
+        Ok(())
+    }
+
+    /// Returns the handles of all messages of the log
+    pub async fn messages(&self) -> Vec<MessageHandle> {
+        self.shared.messages.lock().await.handles.clone()
+    }
+
+    /// Stops the log and deletes all of its messages
+    pub async fn delete_all(&self) -> Result<()> {
+        self.shared.state.lock().unwrap().closed = true;
+        let messages = self.shared.messages.lock().await;
+
+        for handle in &messages.handles {
+            self.shared.listeners.remove(handle);
+            self.http
+                .delete_message(handle.channel_id, handle.message_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A message that displays appended lines in a code block
+/// and continues in a new message when it is full
+pub struct LiveLogMessage {
+    shared: Arc<LogShared>,
+}
+
+#[async_trait]
+impl EventDrivenMessage for LiveLogMessage {
+    fn is_frozen(&self) -> bool {
+        let state = self.shared.state.lock().unwrap();
+
+        state.closed && state.pending.is_empty()
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        self.shared.flush(http).await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, _ctx: &Context) -> Result<()> {
+        tracing::debug!("Live log message deleted. Stopping log");
+        let mut state = self.shared.state.lock().unwrap();
+        state.closed = true;
+        state.pending.clear();
+
+        Ok(())
+    }
+}
+
+/// A builder for live log messages
+pub struct LiveLogBuilder {
+    language: String,
+    max_length: usize,
+    min_interval: Duration,
+}
+
+impl Default for LiveLogBuilder {
+    fn default() -> Self {
+        Self {
+            language: String::new(),
+            max_length: MAX_CONTENT_LENGTH,
+            min_interval: DEFAULT_LOG_INTERVAL,
+        }
+    }
+}
+
+impl LiveLogBuilder {
+    /// Creates a new live log builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the language used for highlighting the code block
+    pub fn language<S: ToString>(mut self, language: S) -> Self {
+        self.language = language.to_string();
+
+        self
+    }
+
+    /// Sets the maximum number of characters of a single message
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+
+        self
+    }
+
+    /// Sets the minimum time between two edits
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+
+        self
+    }
+
+    /// Creates the live log in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build(self, ctx: &Context, channel_id: ChannelId) -> Result<LiveLogHandle> {
+        let listeners = get_listeners_from_context(ctx).await?;
+        let shared = LogShared {
+            language: self.language,
+            max_length: self.max_length,
+            min_interval: self.min_interval,
+            listeners: Arc::clone(&listeners),
+            state: std::sync::Mutex::new(LogState {
+                pending: Vec::new(),
+                flush_pending: false,
+                last_edit: Instant::now(),
+                closed: false,
+            }),
+            messages: Mutex::new(LogMessages {
+                handles: Vec::new(),
+                lines: Vec::new(),
+            }),
+        };
+        let content = shared.render(&[]);
+        let message = channel_id.say(ctx, content).await?;
+        let handle = MessageHandle::new(message.channel_id, message.id);
+        shared.messages.lock().await.handles.push(handle);
+        let shared = Arc::new(shared);
+
+        listeners.insert(
+            handle,
+            Arc::new(Mutex::new(
+                Box::new(LiveLogMessage {
+                    shared: Arc::clone(&shared),
+                })
+                .into(),
+            )),
+        );
+
+        Ok(LiveLogHandle {
+            http: Arc::clone(&ctx.http),
+            runtime: Handle::current(),
+            shared,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_never_exceed_the_maximum_length() {
+        for language in ["", "rust"] {
+            for max_length in [20, 100, MAX_CONTENT_LENGTH] {
+                for line_length in [0, 1, max_length - 1, max_length, 3 * max_length + 7] {
+                    let line = "a".repeat(line_length);
+
+                    for piece in split_line(language, &line, max_length) {
+                        let rendered = render_lines(language, std::slice::from_ref(&piece));
+                        assert!(rendered.chars().count() <= max_length);
+                        assert_eq!(
+                            rendered.chars().count(),
+                            rendered_length(language, &[piece])
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn maximum_length_pieces_fill_the_message() {
+        let line = "a".repeat(MAX_CONTENT_LENGTH);
+        let pieces = split_line("", &line, MAX_CONTENT_LENGTH);
+
+        assert_eq!(
+            render_lines("", &pieces[..1]).chars().count(),
+            MAX_CONTENT_LENGTH
+        );
+    }
+
+    #[test]
+    fn rendered_length_counts_line_breaks() {
+        let lines = vec!["first".to_string(), String::new(), "third".to_string()];
+
+        assert_eq!(
+            render_lines("sh", &lines).chars().count(),
+            rendered_length("sh", &lines)
+        );
+    }
+}
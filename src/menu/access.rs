@@ -0,0 +1,99 @@
+use crate::error::{Error, Result};
+use crate::menu::menu::ControlEvent;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serenity::client::Context;
+use serenity::model::id::{RoleId, UserId};
+use serenity::model::Permissions;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub type AccessPredicateArc =
+    Arc<dyn for<'b> Fn(&'b Context, &'b ControlEvent) -> BoxFuture<'b, Result<bool>> + Send + Sync>;
+
+/// Decides which users are allowed to use the controls of a menu
+#[derive(Clone, Default)]
+pub enum AccessPolicy {
+    /// Everyone can use the controls
+    #[default]
+    Anyone,
+    /// Only the given users can use the controls
+    Users(HashSet<UserId>),
+    /// Only members with at least one of the given roles can use the controls
+    Roles(HashSet<RoleId>),
+    /// Only members with all of the given guild permissions can use the controls
+    Permissions(Permissions),
+    /// The given async predicate decides if the controls can be used
+    Predicate(AccessPredicateArc),
+    /// At least one of the given policies needs to permit the usage
+    Any(Vec<AccessPolicy>),
+    /// All of the given policies need to permit the usage
+    All(Vec<AccessPolicy>),
+}
+
+impl AccessPolicy {
+    /// Creates a policy that only permits the given users
+    pub fn users<I: IntoIterator<Item = UserId>>(users: I) -> Self {
+        Self::Users(users.into_iter().collect())
+    }
+
+    /// Creates a policy that only permits members with one of the given roles
+    pub fn roles<I: IntoIterator<Item = RoleId>>(roles: I) -> Self {
+        Self::Roles(roles.into_iter().collect())
+    }
+
+    /// Creates a policy that decides with the given async predicate
+    pub fn predicate<F>(predicate: F) -> Self
+    where
+        F: for<'b> Fn(&'b Context, &'b ControlEvent) -> BoxFuture<'b, Result<bool>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::Predicate(Arc::new(predicate))
+    }
+
+    /// Returns if the user that triggered the event is permitted by the policy
+    pub fn is_permitted<'b>(
+        &'b self,
+        ctx: &'b Context,
+        event: &'b ControlEvent,
+    ) -> BoxFuture<'b, Result<bool>> {
+        async move {
+            let permitted = match self {
+                AccessPolicy::Anyone => true,
+                AccessPolicy::Users(users) => {
+                    users.contains(&event.user_id().ok_or(Error::NoCache)?)
+                }
+                AccessPolicy::Roles(roles) => event
+                    .member_roles(ctx)
+                    .await?
+                    .iter()
+                    .any(|r| roles.contains(r)),
+                AccessPolicy::Permissions(permissions) => {
+                    event.member_permissions(ctx).await?.contains(*permissions)
+                }
+                AccessPolicy::Predicate(predicate) => predicate(ctx, event).await?,
+                AccessPolicy::Any(policies) => {
+                    for policy in policies {
+                        if policy.is_permitted(ctx, event).await? {
+                            return Ok(true);
+                        }
+                    }
+                    false
+                }
+                AccessPolicy::All(policies) => {
+                    for policy in policies {
+                        if !policy.is_permitted(ctx, event).await? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+            };
+
+            Ok(permitted)
+        }
+        .boxed()
+    }
+}
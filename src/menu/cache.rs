@@ -0,0 +1,132 @@
+use crate::error::Result;
+use crate::menu::page::{MessageBuilderFn, Page};
+use serenity::builder::CreateMessage;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// The default time builder pages are cached for
+pub static DEFAULT_PAGE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+struct CacheEntry {
+    message: CreateMessage<'static>,
+    created_at: Instant,
+}
+
+/// Memoizes the output of builder pages for a limited time
+/// and prefetches pages in the background
+#[derive(Clone)]
+pub(crate) struct PageCache {
+    builders: Arc<HashMap<usize, MessageBuilderFn<'static>>>,
+    entries: Arc<Mutex<HashMap<usize, CacheEntry>>>,
+    pending: Arc<Mutex<HashSet<usize>>>,
+    ttl: Duration,
+    page_count: usize,
+    prefetch_distance: usize,
+}
+
+impl PageCache {
+    /// Creates a new cache for all builder pages in the given list
+    pub fn new(pages: &[Page<'static>], ttl: Duration, prefetch_distance: usize) -> Self {
+        let builders = pages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| match p {
+                Page::Builder(b) => Some((i, Arc::clone(b))),
+                Page::Static(_) => None,
+            })
+            .collect();
+
+        Self {
+            builders: Arc::new(builders),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            pending: Arc::new(Mutex::new(HashSet::new())),
+            ttl,
+            page_count: pages.len(),
+            prefetch_distance,
+        }
+    }
+
+    /// Returns if the page with the given index is cached by this cache
+    pub fn contains(&self, index: usize) -> bool {
+        self.builders.contains_key(&index)
+    }
+
+    /// Returns the cached message of the page or builds and caches it
+    /// if there's no entry or the entry has expired
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn get(&self, index: usize) -> Result<Option<CreateMessage<'static>>> {
+        if let Some(message) = self.get_fresh(index).await {
+            tracing::trace!("Cache hit for page {}", index);
+            return Ok(Some(message));
+        }
+        let builder = match self.builders.get(&index) {
+            Some(builder) => builder,
+            None => return Ok(None),
+        };
+        tracing::trace!("Cache miss for page {}", index);
+        let message = builder().await?;
+        self.insert(index, message.clone()).await;
+
+        Ok(Some(message))
+    }
+
+    /// Builds the pages around the given page in the background
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn prefetch(&self, index: usize) {
+        if self.page_count == 0 {
+            return;
+        }
+        let distance = self.prefetch_distance.min(self.page_count / 2);
+
+        for offset in 1..=distance {
+            let next = (index + offset) % self.page_count;
+            let previous = (index + self.page_count - offset) % self.page_count;
+
+            for neighbour in [next, previous] {
+                self.spawn_prefetch(neighbour).await;
+            }
+        }
+    }
+
+    async fn spawn_prefetch(&self, index: usize) {
+        let builder = match self.builders.get(&index) {
+            Some(builder) => Arc::clone(builder),
+            None => return,
+        };
+        if self.get_fresh(index).await.is_some() || !self.pending.lock().await.insert(index) {
+            return;
+        }
+        let cache = self.clone();
+
+        tokio::spawn(async move {
+            tracing::trace!("Prefetching page {}", index);
+            match builder().await {
+                Ok(message) => cache.insert(index, message).await,
+                Err(e) => tracing::debug!("Failed to prefetch page {}: {:?}", index, e),
+            }
+            cache.pending.lock().await.remove(&index);
+        });
+    }
+
+    async fn get_fresh(&self, index: usize) -> Option<CreateMessage<'static>> {
+        let entries = self.entries.lock().await;
+
+        entries
+            .get(&index)
+            .filter(|e| e.created_at.elapsed() < self.ttl)
+            .map(|e| e.message.clone())
+    }
+
+    async fn insert(&self, index: usize, message: CreateMessage<'static>) {
+        let mut entries = self.entries.lock().await;
+        entries.insert(
+            index,
+            CacheEntry {
+                message,
+                created_at: Instant::now(),
+            },
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::error::Result;
+use crate::menu::access::AccessPolicy;
+use crate::menu::controls::close_menu;
+use crate::menu::menu::{ControlEvent, ControlMode, Menu, MenuBuilder};
+use crate::menu::Page;
+use futures::FutureExt;
+use serenity::builder::CreateMessage;
+use serenity::client::Context;
+use serenity::model::id::ChannelId;
+use std::time::Duration;
+
+pub static CONFIRM_EMOJI: &str = "✅";
+pub static DENY_EMOJI: &str = "❌";
+
+/// The answer to a confirmation dialog
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Confirmation {
+    Confirmed,
+    Denied,
+    /// Nobody answered before the timeout was reached
+    /// or the dialog was closed otherwise
+    TimedOut,
+}
+
+/// Shows a dialog with the given prompt and waits until a user
+/// confirms or denies it or the timeout is reached
+pub async fn confirm<S: ToString>(
+    ctx: &Context,
+    channel_id: ChannelId,
+    prompt: S,
+    timeout: Duration,
+) -> Result<Confirmation> {
+    ConfirmDialog::new(prompt)
+        .timeout(timeout)
+        .show(ctx, channel_id)
+        .await
+}
+
+/// A yes/no dialog that resolves once a permitted user answered it
+pub struct ConfirmDialog {
+    prompt: Page<'static>,
+    timeout: Duration,
+    access: AccessPolicy,
+    control_mode: ControlMode,
+}
+
+impl ConfirmDialog {
+    /// Creates a new dialog with the given prompt as message content
+    pub fn new<S: ToString>(prompt: S) -> Self {
+        let mut message = CreateMessage::default();
+        message.content(prompt);
+
+        Self::with_page(Page::new_static(message))
+    }
+
+    /// Creates a new dialog that displays the given page as prompt
+    pub fn with_page(prompt: Page<'static>) -> Self {
+        Self {
+            prompt,
+            timeout: Duration::from_secs(60),
+            access: AccessPolicy::default(),
+            control_mode: ControlMode::default(),
+        }
+    }
+
+    /// Sets the time to wait for an answer
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Sets the policy that decides who can answer the dialog
+    pub fn access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access = policy;
+
+        self
+    }
+
+    /// Sets if the answers are displayed as reactions or buttons
+    pub fn control_mode(mut self, mode: ControlMode) -> Self {
+        self.control_mode = mode;
+
+        self
+    }
+
+    /// Shows the dialog in the given channel and waits for the answer
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn show(self, ctx: &Context, channel_id: ChannelId) -> Result<Confirmation> {
+        let handle = MenuBuilder::default()
+            .add_page(self.prompt)
+            .timeout(self.timeout)
+            .access_policy(self.access)
+            .control_mode(self.control_mode)
+            .add_control(0, CONFIRM_EMOJI, |c, m, e| {
+                answer(c, m, e, Confirmation::Confirmed).boxed()
+            })
+            .add_control(1, DENY_EMOJI, |c, m, e| {
+                answer(c, m, e, Confirmation::Denied).boxed()
+            })
+            .build_with_handle(ctx, channel_id)
+            .await?;
+
+        Ok(handle
+            .wait()
+            .await
+            .result::<Confirmation>()
+            .unwrap_or(Confirmation::TimedOut))
+    }
+}
+
+/// Answers the dialog and closes it
+async fn answer(
+    ctx: &Context,
+    menu: &mut Menu<'_>,
+    event: ControlEvent,
+    confirmation: Confirmation,
+) -> Result<()> {
+    tracing::debug!("Dialog answered with {:?}", confirmation);
+    menu.set_result(confirmation);
+
+    close_menu(ctx, menu, event).await
+}
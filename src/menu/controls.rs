@@ -1,50 +1,146 @@
 use crate::error::{Error, Result};
-use crate::menu::menu::Menu;
+use crate::menu::get_listeners_from_context;
+use crate::menu::hooks::CloseReason;
+use crate::menu::menu::{Control, ControlEvent, Menu};
 use crate::menu::typedata::HelpActiveContainer;
-use crate::menu::{get_listeners_from_context, ActionContainer};
 use serde_json::json;
 use serde_json::Value;
 use serenity::client::Context;
 use serenity::http::CacheHttp;
-use serenity::model::channel::Reaction;
+use std::mem;
 use std::sync::atomic::Ordering;
 
 /// Shows the next page in the menu
 #[tracing::instrument(level = "debug", skip_all)]
-pub async fn next_page(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Result<()> {
-    menu.current_page = (menu.current_page + 1) % menu.pages.len();
-    display_page(ctx, menu).await?;
+pub async fn next_page(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    match menu.page_count() {
+        Some(count) => {
+            menu.current_page = (menu.current_page + 1) % count;
+            display_page(ctx, menu).await?;
+        }
+        None => try_display_page(ctx, menu, menu.current_page + 1).await?,
+    }
 
     Ok(())
 }
 
 /// Shows the previous page in the menu
 #[tracing::instrument(level = "debug", skip_all)]
-pub async fn previous_page(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Result<()> {
-    if menu.current_page == 0 {
-        menu.current_page = menu.pages.len() - 1;
+pub async fn previous_page(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    if menu.current_page > 0 {
+        menu.current_page -= 1;
+    } else if let Some(count) = menu.page_count() {
+        menu.current_page = count - 1;
     } else {
-        menu.current_page = menu.current_page - 1;
+        tracing::debug!("The number of pages is unknown. Staying on the first page");
+        return Ok(());
     }
     display_page(ctx, menu).await?;
 
     Ok(())
 }
 
-/// Shows the previous page in the menu
+/// Shows the first page in the menu
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn first_page(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    menu.current_page = 0;
+    display_page(ctx, menu).await?;
+
+    Ok(())
+}
+
+/// Shows the last page in the menu
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn last_page(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    if let Some(count) = menu.page_count() {
+        menu.current_page = count.saturating_sub(1);
+        display_page(ctx, menu).await?;
+    } else {
+        tracing::debug!("The number of pages is unknown. Can't display the last page");
+    }
+
+    Ok(())
+}
+
+/// Moves the given amount of pages forward (positive) or backward (negative).
+/// Stops at the first or last page instead of wrapping around.
+/// If the number of pages is unknown and the target page doesn't exist
+/// the current page is kept
+#[tracing::instrument(level = "debug", skip(ctx, menu))]
+pub async fn skip_pages(ctx: &Context, menu: &mut Menu<'_>, amount: isize) -> Result<()> {
+    if amount < 0 {
+        menu.current_page = menu.current_page.saturating_sub(amount.unsigned_abs());
+        display_page(ctx, menu).await?;
+    } else if let Some(count) = menu.page_count() {
+        menu.current_page = menu
+            .current_page
+            .saturating_add(amount as usize)
+            .min(count.saturating_sub(1));
+        display_page(ctx, menu).await?;
+    } else {
+        try_display_page(ctx, menu, menu.current_page.saturating_add(amount as usize)).await?;
+    }
+
+    Ok(())
+}
+
+/// Shows the page that was selected in the page select
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn jump_to_page(ctx: &Context, menu: &mut Menu<'_>, event: ControlEvent) -> Result<()> {
+    let page = if let ControlEvent::Component(interaction) = event {
+        interaction
+            .data
+            .values
+            .first()
+            .and_then(|v| v.parse::<usize>().ok())
+    } else {
+        None
+    };
+
+    match (page, menu.page_count()) {
+        (Some(page), Some(count)) if page < count => {
+            menu.current_page = page;
+            display_page(ctx, menu).await?;
+        }
+        (Some(page), None) => try_display_page(ctx, menu, page).await?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Closes the menu
 #[tracing::instrument(level = "debug", skip_all)]
-pub async fn close_menu(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Result<()> {
-    menu.close(ctx.http()).await?;
+pub async fn close_menu(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    if let Some(view) = menu.private_view.as_mut() {
+        tracing::debug!("Closing private view");
+        view.closed = true;
+        return Ok(());
+    }
+    if let Err(e) = menu.close(ctx.http()).await {
+        tracing::error!("Failed to remove menu controls: {:?}", e);
+    }
+    menu.finish(ctx.http(), CloseReason::CloseControl).await?;
     let message = menu.message.read().await;
-    let listeners = get_listeners_from_context(&ctx).await?;
+    let listeners = get_listeners_from_context(ctx).await?;
     listeners.remove(&*message);
 
     Ok(())
 }
 
+/// Goes back from a submenu to its parent menu
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn go_back(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
+    if !menu.close_submenu(ctx.http()).await? {
+        tracing::debug!("No submenu open");
+    }
+
+    Ok(())
+}
+
 /// Shows a help menu
 #[tracing::instrument(level = "debug", skip_all)]
-pub async fn toggle_help(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Result<()> {
+pub async fn toggle_help(ctx: &Context, menu: &mut Menu<'_>, _: ControlEvent) -> Result<()> {
     let show_help = menu
         .data
         .get::<HelpActiveContainer>()
@@ -56,54 +152,47 @@ pub async fn toggle_help(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Res
         show_help.store(false, Ordering::Relaxed);
         return Ok(());
     }
-    let page = menu
-        .pages
-        .get(menu.current_page)
-        .ok_or(Error::PageNotFound(menu.current_page))?
-        .get()
-        .await?;
-    let mut message = menu.get_message(ctx.http()).await?;
+    let mut page = menu.render_current_page().await?;
     tracing::debug!("Building help entries");
     let mut help_entries = menu
         .help_entries
         .iter()
-        .filter_map(|(e, h)| Some((menu.controls.get(e)?, e, h)))
-        .collect::<Vec<(&ActionContainer, &String, &String)>>();
-    help_entries.sort_by_key(|(c, _, _)| c.position());
+        .filter_map(|(k, h)| Some((menu.controls.get(k)?, h)))
+        .collect::<Vec<(&Control, &String)>>();
+    help_entries.sort_by_key(|(c, _)| c.action.position());
     let help_message = help_entries
         .into_iter()
-        .map(|(_, e, h)| format!(" - {} {}", e, h))
+        .map(|(c, h)| format!(" - {} {}", c.emoji, h))
         .collect::<Vec<String>>()
         .join("\n");
     tracing::trace!("Help message is {}", help_message);
 
-    message
-        .edit(ctx, |m| {
-            m.0.clone_from(&mut page.0.clone());
-
-            if let Some(embed) = m.0.get_mut("embed") {
-                let embed = embed.as_object_mut().unwrap();
-                let fields = embed
-                    .entry("fields")
-                    .or_insert_with(|| Value::Array(vec![]));
-                if let Value::Array(ref mut inner) = *fields {
-                    inner.push(json!({
-                        "inline": false,
-                        "name": "Help".to_string(),
-                        "value": help_message,
-                    }));
-                }
-            } else {
-                m.embed(|e| {
-                    e.field("Help", help_message, false);
-
-                    e
-                });
-            }
-
-            m
-        })
-        .await?;
+    let embed = page
+        .0
+        .get_mut("embeds")
+        .and_then(Value::as_array_mut)
+        .and_then(|embeds| embeds.last_mut())
+        .and_then(Value::as_object_mut);
+
+    if let Some(embed) = embed {
+        let fields = embed
+            .entry("fields")
+            .or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(ref mut inner) = *fields {
+            inner.push(json!({
+                "inline": false,
+                "name": "Help".to_string(),
+                "value": help_message,
+            }));
+        }
+    } else {
+        page.embed(|e| {
+            e.field("Help", help_message, false);
+
+            e
+        });
+    }
+    menu.display(ctx.http(), page).await?;
     tracing::debug!("Help message displayed");
     show_help.store(true, Ordering::Relaxed);
 
@@ -114,20 +203,24 @@ pub async fn toggle_help(ctx: &Context, menu: &mut Menu<'_>, _: Reaction) -> Res
 #[tracing::instrument(level = "debug", skip_all)]
 pub async fn display_page(ctx: &Context, menu: &mut Menu<'_>) -> Result<()> {
     tracing::debug!("Displaying page {}", menu.current_page);
-    let page = menu
-        .pages
-        .get(menu.current_page)
-        .ok_or(Error::PageNotFound(menu.current_page))?
-        .get()
-        .await?;
-    let mut msg = menu.get_message(ctx.http()).await?;
-
-    msg.edit(ctx, |e| {
-        e.0.clone_from(&page.0);
-        e
-    })
-    .await?;
+    let page = menu.render_current_page().await?;
+    menu.display(ctx.http(), page).await?;
     tracing::debug!("Page displayed");
 
     Ok(())
 }
+
+/// Displays the given page and keeps the current page if the given one doesn't exist
+#[tracing::instrument(level = "debug", skip(ctx, menu))]
+pub async fn try_display_page(ctx: &Context, menu: &mut Menu<'_>, page: usize) -> Result<()> {
+    let previous_page = mem::replace(&mut menu.current_page, page);
+
+    match display_page(ctx, menu).await {
+        Err(Error::PageNotFound(_)) => {
+            tracing::debug!("Page {} doesn't exist", page);
+            menu.current_page = previous_page;
+            Ok(())
+        }
+        result => result,
+    }
+}
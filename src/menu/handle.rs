@@ -0,0 +1,61 @@
+use crate::core::MessageHandle;
+use crate::menu::hooks::CloseReason;
+use std::any::Any;
+use std::sync::Arc;
+use tokio::sync::{oneshot, RwLock};
+
+pub type MenuResultBox = Box<dyn Any + Send + Sync>;
+
+/// The outcome of a closed menu
+pub struct MenuOutcome {
+    /// The reason the menu was closed
+    pub reason: CloseReason,
+    result: Option<MenuResultBox>,
+}
+
+impl MenuOutcome {
+    pub(crate) fn new(reason: CloseReason, result: Option<MenuResultBox>) -> Self {
+        Self { reason, result }
+    }
+
+    /// Returns the result that was set by a control of the menu
+    /// if it has the given type
+    pub fn result<T: 'static>(self) -> Option<T> {
+        self.result?.downcast::<T>().ok().map(|r| *r)
+    }
+
+    /// Returns if a result was set
+    pub fn has_result(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+/// A handle to a running menu that can be awaited until the menu is closed
+pub struct MenuHandle {
+    message: Arc<RwLock<MessageHandle>>,
+    receiver: oneshot::Receiver<MenuOutcome>,
+}
+
+impl MenuHandle {
+    pub(crate) fn new(
+        message: Arc<RwLock<MessageHandle>>,
+        receiver: oneshot::Receiver<MenuOutcome>,
+    ) -> Self {
+        Self { message, receiver }
+    }
+
+    /// Returns the handle of the menu message.
+    /// The handle changes when a sticky menu is recreated
+    pub fn message(&self) -> Arc<RwLock<MessageHandle>> {
+        Arc::clone(&self.message)
+    }
+
+    /// Waits for the menu to be closed and returns the outcome.
+    /// Returns an outcome with [CloseReason::Shutdown] if the menu
+    /// stopped without being closed
+    pub async fn wait(self) -> MenuOutcome {
+        self.receiver
+            .await
+            .unwrap_or_else(|_| MenuOutcome::new(CloseReason::Shutdown, None))
+    }
+}
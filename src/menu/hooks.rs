@@ -0,0 +1,36 @@
+use crate::menu::menu::{ControlActionResult, Menu};
+use serenity::http::Http;
+use std::sync::Arc;
+
+pub type MenuHookArc =
+    Arc<dyn for<'b> Fn(&'b Http, &'b mut Menu<'_>) -> ControlActionResult<'b> + Send + Sync>;
+
+pub type CloseHookArc = Arc<
+    dyn for<'b> Fn(&'b Http, &'b mut Menu<'_>, CloseReason) -> ControlActionResult<'b>
+        + Send
+        + Sync,
+>;
+
+/// The reason a menu was closed
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CloseReason {
+    /// The timeout of the menu was reached
+    Timeout,
+    /// The close control of the menu was used
+    CloseControl,
+    /// The menu message was deleted
+    MessageDeleted,
+    /// The bot is shutting down
+    Shutdown,
+}
+
+/// Hooks that are run at different stages of the menus lifecycle
+#[derive(Clone, Default)]
+pub struct MenuHooks {
+    /// Run once the menu message has been created
+    pub on_open: Option<MenuHookArc>,
+    /// Run after a control changed the current page
+    pub on_page_change: Option<MenuHookArc>,
+    /// Run once when the menu is closed
+    pub on_close: Option<CloseHookArc>,
+}
@@ -0,0 +1,273 @@
+use crate::menu::page::Page;
+use crate::menu::text::{
+    truncate, MAX_EMBED_DESCRIPTION_LENGTH, MAX_EMBED_FOOTER_LENGTH, MAX_EMBED_LENGTH,
+    MAX_EMBED_TITLE_LENGTH,
+};
+use serenity::builder::CreateMessage;
+use serenity::utils::Colour;
+use std::mem;
+
+/// The default number of items displayed on one page of an item list
+pub static DEFAULT_ITEMS_PER_PAGE: usize = 10;
+
+pub type ItemFormatterFn<T> = Box<dyn Fn(usize, &T) -> String + Send + Sync>;
+
+/// Builds embed pages that display a list of items with a maximum number of items per page.
+/// The title, header and footer may contain `{page}` for the one-based page number,
+/// `{total}` for the number of pages and `{count}` for the number of items
+pub struct ItemList<T> {
+    items: Vec<T>,
+    formatter: ItemFormatterFn<T>,
+    items_per_page: usize,
+    separator: String,
+    title: Option<String>,
+    colour: Option<Colour>,
+    header: Option<String>,
+    footer: Option<String>,
+    empty_text: String,
+}
+
+impl<T> ItemList<T> {
+    /// Creates a new item list with the given items.
+    /// The formatter is called with the index of the item in the list and the item
+    pub fn new<I, F>(items: I, formatter: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(usize, &T) -> String + Send + Sync + 'static,
+    {
+        Self {
+            items: items.into_iter().collect(),
+            formatter: Box::new(formatter),
+            items_per_page: DEFAULT_ITEMS_PER_PAGE,
+            separator: "\n".to_string(),
+            title: None,
+            colour: None,
+            header: None,
+            footer: None,
+            empty_text: "No entries".to_string(),
+        }
+    }
+
+    /// Sets the number of items displayed on one page
+    pub fn items_per_page(mut self, amount: usize) -> Self {
+        self.items_per_page = amount.max(1);
+
+        self
+    }
+
+    /// Sets the text placed between two items
+    pub fn separator<S: ToString>(mut self, separator: S) -> Self {
+        self.separator = separator.to_string();
+
+        self
+    }
+
+    /// Sets the title template of every page
+    pub fn title<S: ToString>(mut self, title: S) -> Self {
+        self.title = Some(title.to_string());
+
+        self
+    }
+
+    /// Sets the colour of every page
+    pub fn colour<C: Into<Colour>>(mut self, colour: C) -> Self {
+        self.colour = Some(colour.into());
+
+        self
+    }
+
+    /// Sets the template of the text displayed above the items
+    pub fn header<S: ToString>(mut self, header: S) -> Self {
+        self.header = Some(header.to_string());
+
+        self
+    }
+
+    /// Sets the template of the embed footer
+    pub fn footer<S: ToString>(mut self, footer: S) -> Self {
+        self.footer = Some(footer.to_string());
+
+        self
+    }
+
+    /// Sets the text displayed when the list has no items
+    pub fn empty_text<S: ToString>(mut self, text: S) -> Self {
+        self.empty_text = text.to_string();
+
+        self
+    }
+
+    /// Creates the pages of the list.
+    /// A page holds fewer items when they would exceed the description length of an embed.
+    /// Items, titles and footers that are too long are truncated
+    pub fn build_pages(self) -> Vec<Page<'static>> {
+        let count = self.items.len();
+        // the header is rendered with the largest possible numbers to reserve enough room
+        let header_length = self.header.as_ref().map_or(0, |header| {
+            let header = render_template(header, count.max(1), count.max(1), count);
+            char_count(&header).min(MAX_EMBED_DESCRIPTION_LENGTH / 2) + 2
+        });
+        let max_entries_length = MAX_EMBED_DESCRIPTION_LENGTH - header_length;
+        let separator_length = char_count(&self.separator);
+
+        let mut pages: Vec<Vec<String>> = Vec::new();
+        let mut page_entries = Vec::new();
+        let mut page_length = 0;
+
+        for (i, item) in self.items.iter().enumerate() {
+            let entry = truncate(&(self.formatter)(i, item), max_entries_length);
+            let entry_length = char_count(&entry);
+
+            if !page_entries.is_empty()
+                && (page_entries.len() >= self.items_per_page
+                    || page_length + separator_length + entry_length > max_entries_length)
+            {
+                pages.push(mem::take(&mut page_entries));
+                page_length = 0;
+            }
+            if !page_entries.is_empty() {
+                page_length += separator_length;
+            }
+            page_length += entry_length;
+            page_entries.push(entry);
+        }
+        if !page_entries.is_empty() {
+            pages.push(page_entries);
+        }
+        let total = pages.len().max(1);
+        let mut entries = pages
+            .into_iter()
+            .map(|p| p.join(&self.separator))
+            .collect::<Vec<String>>();
+
+        if entries.is_empty() {
+            entries.push(truncate(&self.empty_text, max_entries_length));
+        }
+
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(page, entries)| {
+                let render = |template: &String| render_template(template, page + 1, total, count);
+                let description = match &self.header {
+                    Some(header) => format!(
+                        "{}\n\n{}",
+                        truncate(&render(header), MAX_EMBED_DESCRIPTION_LENGTH / 2),
+                        entries
+                    ),
+                    None => entries,
+                };
+                let title = self
+                    .title
+                    .as_ref()
+                    .map(|t| truncate(&render(t), MAX_EMBED_TITLE_LENGTH));
+                let footer = self.footer.as_ref().map(|f| {
+                    let used = char_count(&description) + title.as_deref().map_or(0, char_count);
+                    let max_length =
+                        MAX_EMBED_FOOTER_LENGTH.min(MAX_EMBED_LENGTH.saturating_sub(used));
+                    truncate(&render(f), max_length)
+                });
+                let mut message = CreateMessage::default();
+                message.embed(|e| {
+                    e.description(description);
+
+                    if let Some(title) = title {
+                        e.title(title);
+                    }
+                    if let Some(colour) = self.colour {
+                        e.colour(colour);
+                    }
+                    if let Some(footer) = footer {
+                        e.footer(|f| f.text(footer));
+                    }
+
+                    e
+                });
+
+                Page::new_static(message)
+            })
+            .collect()
+    }
+}
+
+/// Replaces the placeholders of the template
+fn render_template(template: &str, page: usize, total: usize, count: usize) -> String {
+    template
+        .replace("{page}", &page.to_string())
+        .replace("{total}", &total.to_string())
+        .replace("{count}", &count.to_string())
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn embed(page: &Page<'static>) -> Value {
+        match page {
+            Page::Static(message) => message.0["embeds"][0].clone(),
+            Page::Builder(_) => unreachable!(),
+        }
+    }
+
+    fn description(page: &Page<'static>) -> String {
+        embed(page)["description"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn groups_items_per_page() {
+        let pages = ItemList::new(1..=25, |_, i| i.to_string())
+            .items_per_page(10)
+            .header("Page {page}/{total} of {count}")
+            .build_pages();
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            description(&pages[0]),
+            "Page 1/3 of 25\n\n1\n2\n3\n4\n5\n6\n7\n8\n9\n10"
+        );
+        assert_eq!(
+            description(&pages[2]),
+            "Page 3/3 of 25\n\n21\n22\n23\n24\n25"
+        );
+    }
+
+    #[test]
+    fn empty_lists_show_the_empty_text() {
+        let pages = ItemList::new(Vec::<u32>::new(), |_, i| i.to_string()).build_pages();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(description(&pages[0]), "No entries");
+    }
+
+    #[test]
+    fn long_items_are_spread_over_more_pages() {
+        let pages = ItemList::new(0..10, |_, _| "a".repeat(1000))
+            .header("Header")
+            .build_pages();
+
+        assert_eq!(pages.len(), 3);
+        for page in &pages {
+            assert!(char_count(&description(page)) <= MAX_EMBED_DESCRIPTION_LENGTH);
+        }
+    }
+
+    #[test]
+    fn oversized_texts_are_truncated() {
+        let pages = ItemList::new([0], |_, _| "a".repeat(5000))
+            .title("t".repeat(300))
+            .footer("f".repeat(3000))
+            .build_pages();
+        let embed = embed(&pages[0]);
+        let length = |key: &str| char_count(embed[key].as_str().unwrap());
+        let footer = char_count(embed["footer"]["text"].as_str().unwrap());
+
+        assert_eq!(length("description"), MAX_EMBED_DESCRIPTION_LENGTH);
+        assert_eq!(length("title"), MAX_EMBED_TITLE_LENGTH);
+        assert!(length("description") + length("title") + footer <= MAX_EMBED_LENGTH);
+    }
+}
@@ -1,38 +1,179 @@
 use crate::core::MessageHandle;
 use crate::error::{Error, Result};
-use crate::menu::controls::{close_menu, next_page, previous_page, toggle_help};
+use crate::menu::access::AccessPolicy;
+use crate::menu::cache::{PageCache, DEFAULT_PAGE_CACHE_TTL};
+use crate::menu::controls::{
+    close_menu, first_page, go_back, jump_to_page, last_page, next_page, previous_page, skip_pages,
+    toggle_help,
+};
+use crate::menu::handle::{MenuHandle, MenuOutcome, MenuResultBox};
+use crate::menu::hooks::{CloseReason, MenuHookArc, MenuHooks};
+use crate::menu::list::ItemList;
+use crate::menu::table::Table;
+use crate::menu::text::{text_pages, TextPageStyle};
 use crate::menu::traits::EventDrivenMessage;
 use crate::menu::typedata::HelpActiveContainer;
-use crate::menu::{get_listeners_from_context, EventDrivenMessagesRef, Page};
+use crate::menu::{
+    get_listeners_from_context, EventDrivenMessagesRef, Page, PageIndicator, PageSource,
+};
+use crate::sticky::{StickyDebounce, DEFAULT_STICKY_DEBOUNCE};
 use futures::FutureExt;
+use serde_json::json;
 use serenity::async_trait;
+use serenity::builder::{CreateComponents, CreateMessage};
 use serenity::client::Context;
 use serenity::http::Http;
 use serenity::model::channel::{Message, Reaction, ReactionType};
-use serenity::model::id::{ChannelId, UserId};
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
+use serenity::model::interactions::{
+    InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+};
+use serenity::model::Permissions;
 use serenity::prelude::{TypeMap, TypeMapKey};
 use std::collections::HashMap;
 use std::future::Future;
 use std::mem;
 use std::pin::Pin;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 pub static NEXT_PAGE_EMOJI: &str = "➡️";
 pub static PREVIOUS_PAGE_EMOJI: &str = "⬅️";
 pub static CLOSE_MENU_EMOJI: &str = "❌";
 pub static HELP_EMOJI: &str = "❔";
+pub static FIRST_PAGE_EMOJI: &str = "⏮️";
+pub static LAST_PAGE_EMOJI: &str = "⏭️";
+pub static SKIP_BACKWARD_EMOJI: &str = "⏪";
+pub static SKIP_FORWARD_EMOJI: &str = "⏩";
+pub static BACK_EMOJI: &str = "↩️";
+
+/// The amount of pages skipped by the skip controls of [MenuBuilder::new_paginator_extended]
+pub static DEFAULT_SKIP_AMOUNT: usize = 5;
+
+/// Custom id prefix of the select menus used to jump to a page
+pub static PAGE_SELECT_ID: &str = "menu_page_select";
+
+/// Discord allows up to five buttons per action row
+pub(crate) const BUTTONS_PER_ROW: usize = 5;
+/// Discord allows up to five action rows per message
+pub(crate) const MAX_ACTION_ROWS: usize = 5;
+/// Discord allows up to 25 options per select menu
+pub(crate) const OPTIONS_PER_SELECT: usize = 25;
+/// Discord allows up to 100 characters per select menu option label
+pub(crate) const MAX_OPTION_LABEL_LENGTH: usize = 100;
 
 pub type ControlActionResult<'b> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'b>>;
 
 pub type ControlActionArc = Arc<
-    dyn for<'b> Fn(&'b Context, &'b mut Menu<'_>, Reaction) -> ControlActionResult<'b>
+    dyn for<'b> Fn(&'b Context, &'b mut Menu<'_>, ControlEvent) -> ControlActionResult<'b>
         + Send
         + Sync,
 >;
 
+/// What happens to a menu when its timeout is reached
+#[derive(Clone, Default)]
+pub enum ExpiryAction {
+    /// Removes the controls from the menu message
+    #[default]
+    RemoveControls,
+    /// Deletes the menu message
+    Delete,
+    /// Replaces the content of the menu message with
+    /// the given page and removes the controls
+    ShowPage(Page<'static>),
+    /// Leaves the menu message unchanged
+    Keep,
+    /// Runs the given callback
+    Callback(MenuHookArc),
+}
+
+impl ExpiryAction {
+    /// Creates a new expiry action that runs the given callback
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: for<'b> Fn(&'b Http, &'b mut Menu<'_>) -> ControlActionResult<'b>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::Callback(Arc::new(callback))
+    }
+}
+
+/// The way the controls of a menu are displayed and interacted with
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ControlMode {
+    /// Controls are reactions on the menu message
+    #[default]
+    Reactions,
+    /// Controls are buttons attached to the menu message
+    Buttons,
+}
+
+/// The event that triggered a menu control
+#[derive(Clone, Debug)]
+pub enum ControlEvent {
+    Reaction(Box<Reaction>),
+    Component(Box<MessageComponentInteraction>),
+}
+
+impl ControlEvent {
+    /// Returns the id of the user that triggered the control
+    pub fn user_id(&self) -> Option<UserId> {
+        match self {
+            ControlEvent::Reaction(r) => r.user_id,
+            ControlEvent::Component(c) => Some(c.user.id),
+        }
+    }
+
+    /// Returns the id of the guild the control was triggered in
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            ControlEvent::Reaction(r) => r.guild_id,
+            ControlEvent::Component(c) => c.guild_id,
+        }
+    }
+
+    /// Returns the roles of the member that triggered the control.
+    /// Outside of guilds the member has no roles
+    pub async fn member_roles(&self, ctx: &Context) -> Result<Vec<RoleId>> {
+        let roles = match self {
+            ControlEvent::Reaction(r) => match (&r.member, r.guild_id, r.user_id) {
+                (Some(member), _, _) => member.roles.clone(),
+                (None, Some(guild_id), Some(user_id)) => guild_id.member(ctx, user_id).await?.roles,
+                _ => Vec::new(),
+            },
+            ControlEvent::Component(c) => c
+                .member
+                .as_ref()
+                .map(|m| m.roles.clone())
+                .unwrap_or_default(),
+        };
+
+        Ok(roles)
+    }
+
+    /// Returns the guild permissions of the member that triggered the control.
+    /// Outside of guilds the member has no permissions
+    pub async fn member_permissions(&self, ctx: &Context) -> Result<Permissions> {
+        if let ControlEvent::Component(c) = self {
+            if let Some(permissions) = c.member.as_ref().and_then(|m| m.permissions) {
+                return Ok(permissions);
+            }
+        }
+        let (guild_id, user_id) = match (self.guild_id(), self.user_id()) {
+            (Some(guild_id), Some(user_id)) => (guild_id, user_id),
+            _ => return Ok(Permissions::empty()),
+        };
+        let member = guild_id.member(ctx, user_id).await?;
+
+        Ok(member.permissions(ctx)?)
+    }
+}
+
 #[derive(Clone)]
 pub struct ActionContainer {
     inner: ControlActionArc,
@@ -41,11 +182,12 @@ pub struct ActionContainer {
 
 impl ActionContainer {
     /// Creates a new control action
-    pub fn new<F: 'static>(position: isize, callback: F) -> Self
+    pub fn new<F>(position: isize, callback: F) -> Self
     where
-        F: for<'b> Fn(&'b Context, &'b mut Menu<'_>, Reaction) -> ControlActionResult<'b>
+        F: for<'b> Fn(&'b Context, &'b mut Menu<'_>, ControlEvent) -> ControlActionResult<'b>
             + Send
-            + Sync,
+            + Sync
+            + 'static,
     {
         Self {
             inner: Arc::new(callback),
@@ -54,8 +196,8 @@ impl ActionContainer {
     }
 
     /// Runs the action
-    pub async fn run(&self, ctx: &Context, menu: &mut Menu<'_>, reaction: Reaction) -> Result<()> {
-        self.inner.clone()(ctx, menu, reaction).await?;
+    pub async fn run(&self, ctx: &Context, menu: &mut Menu<'_>, event: ControlEvent) -> Result<()> {
+        self.inner.clone()(ctx, menu, event).await?;
         Ok(())
     }
 
@@ -65,36 +207,373 @@ impl ActionContainer {
     }
 }
 
+/// A control of a menu together with the emoji it is displayed with
+#[derive(Clone)]
+pub(crate) struct Control {
+    pub(crate) emoji: ReactionType,
+    pub(crate) action: ActionContainer,
+    pub(crate) access: Option<AccessPolicy>,
+}
+
+impl Control {
+    /// Creates a new control parsing the given emoji
+    pub(crate) fn new<S: ToString>(emoji: S, action: ActionContainer) -> Self {
+        Self {
+            emoji: parse_control_emoji(emoji),
+            action,
+            access: None,
+        }
+    }
+}
+
+/// Parses an emoji given as unicode string or in
+/// the custom emoji format `<:name:id>` or `<a:name:id>`
+pub(crate) fn parse_control_emoji<S: ToString>(emoji: S) -> ReactionType {
+    let emoji = emoji.to_string();
+
+    emoji
+        .parse::<ReactionType>()
+        .unwrap_or(ReactionType::Unicode(emoji))
+}
+
+/// Returns the key a control with the given emoji is stored with.
+/// Custom emojis are identified by their id and unicode emojis
+/// by their content without variation selectors
+pub(crate) fn control_key(emoji: &ReactionType) -> String {
+    match emoji {
+        ReactionType::Custom { id, .. } => id.0.to_string(),
+        ReactionType::Unicode(unicode) => unicode
+            .chars()
+            .filter(|c| *c != '\u{FE0E}' && *c != '\u{FE0F}')
+            .collect(),
+        _ => emoji.as_data(),
+    }
+}
+
+/// The ephemeral view of a single user on a menu with private navigation
+pub(crate) struct PrivateView {
+    /// The token of the interaction the view is currently handled with
+    pub(crate) token: String,
+    /// The id of the ephemeral message of the view once it is displayed
+    pub(crate) message_id: Option<MessageId>,
+    /// If the view was closed by the user
+    pub(crate) closed: bool,
+}
+
 /// A menu message
 pub struct Menu<'a> {
     pub message: Arc<RwLock<MessageHandle>>,
     pub pages: Vec<Page<'a>>,
+    pub page_source: Option<Arc<dyn PageSource>>,
     pub current_page: usize,
-    pub(crate) controls: HashMap<String, ActionContainer>,
+    pub(crate) controls: HashMap<String, Control>,
     pub timeout: Instant,
+    pub idle_timeout: Option<Duration>,
+    pub deadline: Option<Instant>,
     pub sticky: bool,
+    pub(crate) sticky_debounce: StickyDebounce,
     pub data: TypeMap,
+    pub control_mode: ControlMode,
+    pub page_labels: HashMap<usize, String>,
+    pub page_indicator: Option<PageIndicator>,
+    pub expiry_action: ExpiryAction,
+    pub hooks: MenuHooks,
+    pub private_navigation: bool,
+    pub(crate) user_pages: HashMap<UserId, usize>,
+    pub(crate) user_help: HashMap<UserId, bool>,
+    pub(crate) private_view: Option<PrivateView>,
+    pub(crate) page_select: Option<ActionContainer>,
+    pub(crate) page_cache: Option<PageCache>,
     pub(crate) help_entries: HashMap<String, String>,
-    owner: Option<UserId>,
+    pub(crate) parents: Vec<MenuLevel<'a>>,
+    pub access: AccessPolicy,
+    result: Option<MenuResultBox>,
+    completion: Option<oneshot::Sender<MenuOutcome>>,
     closed: bool,
     listeners: EventDrivenMessagesRef,
 }
 
+/// The pages and controls of one level of a menu.
+/// Levels of parent menus are stored while a submenu is open
+pub(crate) struct MenuLevel<'a> {
+    pages: Vec<Page<'a>>,
+    page_source: Option<Arc<dyn PageSource>>,
+    current_page: usize,
+    controls: HashMap<String, Control>,
+    help_entries: HashMap<String, String>,
+    page_labels: HashMap<usize, String>,
+    page_select: Option<ActionContainer>,
+    page_cache: Option<PageCache>,
+}
+
 impl<'a> Menu<'a> {
-    /// Returns the current page of the menu
+    /// Returns the current page of the menu.
+    /// Only knows the pages that were added to the menu directly and returns
+    /// [Error::PageNotFound] for menus with a page source
+    #[deprecated(note = "use `render_current_page` which also supports page sources")]
+    #[allow(clippy::result_large_err)]
     pub fn get_current_page(&self) -> Result<&Page<'a>> {
         self.pages
             .get(self.current_page)
             .ok_or(Error::PageNotFound(self.current_page))
     }
 
-    /// Removes all reactions from the menu
+    /// Returns the number of pages if it is known.
+    /// Menus with a page source might not know their number of pages
+    pub fn page_count(&self) -> Option<usize> {
+        match &self.page_source {
+            Some(source) => source.len(),
+            None => Some(self.pages.len()),
+        }
+    }
+
+    /// Returns the message of the page with the given index
+    /// either from the page source or the static pages
+    pub async fn get_page(&self, index: usize) -> Result<CreateMessage<'a>> {
+        match &self.page_source {
+            Some(source) => source.get(index).await?.ok_or(Error::PageNotFound(index)),
+            None => match self.page_cache.as_ref().filter(|c| c.contains(index)) {
+                Some(cache) => cache.get(index).await?.ok_or(Error::PageNotFound(index)),
+                None => {
+                    self.pages
+                        .get(index)
+                        .ok_or(Error::PageNotFound(index))?
+                        .get()
+                        .await
+                }
+            },
+        }
+    }
+
+    /// Returns the rendered message of the current page
+    /// with the page indicator applied
+    pub async fn render_current_page(&self) -> Result<CreateMessage<'a>> {
+        let mut page = self.get_page(self.current_page).await?;
+
+        if let Some(cache) = &self.page_cache {
+            cache.prefetch(self.current_page).await;
+        }
+        if let Some(indicator) = &self.page_indicator {
+            indicator.apply(&mut page, self.current_page, self.page_count());
+        }
+
+        Ok(page)
+    }
+
+    /// Removes all controls from the menu
     #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) async fn close(&mut self, http: &Http) -> Result<()> {
         let handle = self.message.read().await;
-        http.delete_message_reactions(handle.channel_id, handle.message_id)
+        if self.control_mode == ControlMode::Reactions {
+            http.delete_message_reactions(handle.channel_id, handle.message_id)
+                .await?;
+        }
+        if self.has_components() {
+            http.edit_message(
+                handle.channel_id,
+                handle.message_id,
+                &json!({ "components": [] }),
+            )
             .await?;
+        }
+        Ok(())
+    }
+
+    /// Marks the menu as closed and runs the close hook.
+    /// Does nothing if the menu is already closed
+    #[tracing::instrument(level = "debug", skip(self, http))]
+    pub(crate) async fn finish(&mut self, http: &Http, reason: CloseReason) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
         self.closed = true;
+        self.page_cache = None;
+        self.parents.clear();
+
+        let hook_result = if let Some(on_close) = self.hooks.on_close.clone() {
+            tracing::debug!("Running close hook");
+            on_close(http, self, reason).await
+        } else {
+            Ok(())
+        };
+        if let Some(completion) = self.completion.take() {
+            let outcome = MenuOutcome::new(reason, self.result.take());
+            if completion.send(outcome).is_err() {
+                tracing::debug!("Menu handle was dropped before the menu was closed");
+            }
+        }
+
+        hook_result
+    }
+
+    /// Sets the result of the menu that is passed to the [MenuHandle] when the menu is closed
+    pub fn set_result<T: Send + Sync + 'static>(&mut self, result: T) {
+        self.result = Some(Box::new(result));
+    }
+
+    /// Runs the expiry action of the menu and stops handling events
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn expire(&mut self, http: &Http) -> Result<()> {
+        if let Err(e) = self.run_expiry_action(http).await {
+            tracing::error!("Failed to run expiry action: {:?}", e);
+        }
+
+        self.finish(http, CloseReason::Timeout).await
+    }
+
+    /// Applies the expiry action to the menu message
+    async fn run_expiry_action(&mut self, http: &Http) -> Result<()> {
+        match self.expiry_action.clone() {
+            ExpiryAction::RemoveControls => self.close(http).await?,
+            ExpiryAction::Delete => {
+                let handle = self.get_handle().await;
+                http.delete_message(handle.channel_id, handle.message_id)
+                    .await?;
+            }
+            ExpiryAction::ShowPage(page) => {
+                let handle = self.get_handle().await;
+                let mut page = page.get().await?;
+                if self.has_components() {
+                    page.set_components(CreateComponents::default());
+                }
+                http.edit_message(
+                    handle.channel_id,
+                    handle.message_id,
+                    &serde_json::to_value(page.0).unwrap(),
+                )
+                .await?;
+                if self.control_mode == ControlMode::Reactions {
+                    http.delete_message_reactions(handle.channel_id, handle.message_id)
+                        .await?;
+                }
+            }
+            ExpiryAction::Keep => {}
+            ExpiryAction::Callback(callback) => callback(http, self).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Displays the given page on the menu message or,
+    /// while a private view is handled, on the ephemeral message of the view
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn display(&mut self, http: &Http, mut page: CreateMessage<'_>) -> Result<()> {
+        if let Some(token) = self.private_view.as_ref().map(|v| v.token.clone()) {
+            page.set_components(self.create_components());
+            let message = http
+                .edit_original_interaction_response(&token, &serde_json::to_value(page.0).unwrap())
+                .await?;
+            if let Some(view) = self.private_view.as_mut() {
+                view.message_id = Some(message.id);
+            }
+        } else {
+            if self.page_select.is_some() {
+                // the listed pages of the page select depend on the current page
+                page.set_components(self.create_components());
+            }
+            let handle = self.get_handle().await;
+            http.edit_message(
+                handle.channel_id,
+                handle.message_id,
+                &serde_json::to_value(page.0).unwrap(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Opens the pages and controls of the given builder as a submenu in the same message.
+    /// Only the pages, controls, help entries, page labels, page select and
+    /// page cache settings of the builder are used.
+    /// A control to go back to the current menu is added to the submenu
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn open_submenu(&mut self, http: &Http, submenu: MenuBuilder) -> Result<()> {
+        if self.private_navigation {
+            return Err(Error::Msg(
+                "Submenus are not supported with private navigation".to_string(),
+            ));
+        }
+        let submenu = submenu
+            .add_control(-10, BACK_EMOJI, |c, m, r| go_back(c, m, r).boxed())
+            .add_help(BACK_EMOJI, "Goes back to the previous menu");
+        let page_cache = submenu.create_page_cache();
+
+        let level = MenuLevel {
+            pages: submenu.pages.into_iter().map(Page::into_bounded).collect(),
+            page_source: submenu.page_source,
+            current_page: submenu.current_page,
+            controls: submenu.controls,
+            help_entries: submenu.help_entries,
+            page_labels: submenu.page_labels,
+            page_select: submenu.page_select,
+            page_cache,
+        };
+        let parent = self.swap_level(level);
+        self.parents.push(parent);
+        tracing::debug!("Opened submenu at depth {}", self.parents.len());
+
+        self.refresh(http).await
+    }
+
+    /// Closes the current submenu and restores its parent menu.
+    /// Returns false if there's no submenu open
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn close_submenu(&mut self, http: &Http) -> Result<bool> {
+        let parent = if let Some(parent) = self.parents.pop() {
+            parent
+        } else {
+            return Ok(false);
+        };
+        self.swap_level(parent);
+        tracing::debug!("Closed submenu. Now at depth {}", self.parents.len());
+        self.refresh(http).await?;
+
+        Ok(true)
+    }
+
+    /// Replaces the pages and controls of the menu with the given level
+    /// and returns the previous level
+    fn swap_level(&mut self, level: MenuLevel<'a>) -> MenuLevel<'a> {
+        if !self.data.contains_key::<HelpActiveContainer>() {
+            self.data
+                .insert::<HelpActiveContainer>(Arc::new(AtomicBool::new(false)));
+        }
+        if let Some(show_help) = self.data.get::<HelpActiveContainer>() {
+            show_help.store(false, Ordering::Relaxed);
+        }
+
+        MenuLevel {
+            pages: mem::replace(&mut self.pages, level.pages),
+            page_source: mem::replace(&mut self.page_source, level.page_source),
+            current_page: mem::replace(&mut self.current_page, level.current_page),
+            controls: mem::replace(&mut self.controls, level.controls),
+            help_entries: mem::replace(&mut self.help_entries, level.help_entries),
+            page_labels: mem::replace(&mut self.page_labels, level.page_labels),
+            page_select: mem::replace(&mut self.page_select, level.page_select),
+            page_cache: mem::replace(&mut self.page_cache, level.page_cache),
+        }
+    }
+
+    /// Displays the current page and replaces the controls of the message
+    /// with the current controls
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn refresh(&mut self, http: &Http) -> Result<()> {
+        let mut page = self.render_current_page().await?;
+        page.set_components(self.create_components());
+        self.display(http, page).await?;
+
+        if self.control_mode == ControlMode::Reactions && self.private_view.is_none() {
+            let handle = self.get_handle().await;
+            http.delete_message_reactions(handle.channel_id, handle.message_id)
+                .await?;
+
+            for (_, control) in sorted_controls(&self.controls) {
+                http.create_reaction(handle.channel_id, handle.message_id, &control.emoji)
+                    .await?;
+            }
+        }
+
         Ok(())
     }
 
@@ -112,8 +591,16 @@ impl<'a> Menu<'a> {
     #[tracing::instrument(level = "debug", skip_all)]
     pub async fn recreate(&self, http: &Http) -> Result<()> {
         let old_handle = self.get_handle().await;
-        let current_page = self.get_current_page()?.get().await?;
 
+        if !self.listeners.contains_key(&old_handle) {
+            tracing::debug!("Menu was removed. Skipping recreation");
+            return Ok(());
+        }
+        let mut current_page = self.render_current_page().await?;
+
+        if self.has_components() {
+            current_page.set_components(self.create_components());
+        }
         let message = http
             .send_message(
                 old_handle.channel_id,
@@ -121,25 +608,26 @@ impl<'a> Menu<'a> {
             )
             .await?;
 
-        for control in &self.controls {
-            http.create_reaction(
-                message.channel_id.0,
-                message.id.0,
-                &ReactionType::Unicode(control.0.clone()),
-            )
-            .await?;
+        if self.control_mode == ControlMode::Reactions {
+            for (_, control) in sorted_controls(&self.controls) {
+                http.create_reaction(message.channel_id.0, message.id.0, &control.emoji)
+                    .await?;
+            }
         }
 
-        let new_handle = {
-            let mut handle = self.message.write().await;
-            handle.message_id = message.id.0;
-            (*handle).clone()
-        };
-        {
+        let new_handle = MessageHandle::new(message.channel_id, message.id);
+
+        // the original message may have been deleted while the new one was sent
+        if let Some((_, menu)) = self.listeners.remove(&old_handle) {
             tracing::debug!("Changing key of message");
-            let menu = self.listeners.remove(&old_handle).unwrap();
-            tracing::debug!("Inserting new key");
-            self.listeners.insert(new_handle, menu.1);
+            self.message.write().await.message_id = new_handle.message_id;
+            self.listeners.insert(new_handle, menu);
+        } else {
+            tracing::debug!("Menu was removed during recreation. Deleting new message");
+            http.delete_message(new_handle.channel_id, new_handle.message_id)
+                .await?;
+
+            return Ok(());
         }
         tracing::debug!("Deleting original message");
         http.delete_message(old_handle.channel_id, old_handle.message_id)
@@ -152,10 +640,286 @@ impl<'a> Menu<'a> {
     /// Locking behaviour: May deadlock when already holding a lock to [Self::messages]
     async fn get_handle(&self) -> MessageHandle {
         let handle = self.message.read().await;
-        (*handle).clone()
+        *handle
+    }
+
+    /// Returns the label of the page with the given index
+    pub fn get_page_label(&self, page: usize) -> String {
+        self.page_labels
+            .get(&page)
+            .cloned()
+            .unwrap_or_else(|| format!("Page {}", page + 1))
+    }
+
+    /// Returns if the menu has message components attached
+    pub(crate) fn has_components(&self) -> bool {
+        self.control_mode == ControlMode::Buttons || self.page_select.is_some()
+    }
+
+    /// Creates the message components for the controls and the page select
+    pub(crate) fn create_components(&self) -> CreateComponents {
+        let mut components = CreateComponents::default();
+        let mut rows = 0;
+
+        if self.control_mode == ControlMode::Buttons {
+            for row in sorted_controls(&self.controls).chunks(BUTTONS_PER_ROW) {
+                components.create_action_row(|r| {
+                    for (key, control) in row {
+                        r.create_button(|b| {
+                            b.style(ButtonStyle::Secondary)
+                                .custom_id(key)
+                                .emoji(control.emoji.clone())
+                        });
+                    }
+                    r
+                });
+                rows += 1;
+            }
+        }
+        if self.page_select.is_some() && self.page_count().is_none() {
+            tracing::warn!("The number of pages is unknown. Omitting page select");
+        } else if self.page_select.is_some() {
+            let page_count = self.page_count().unwrap_or_default();
+            let page_indices = (0..page_count).collect::<Vec<usize>>();
+            let chunks = page_indices
+                .chunks(OPTIONS_PER_SELECT)
+                .collect::<Vec<&[usize]>>();
+
+            let available_rows = MAX_ACTION_ROWS.saturating_sub(rows);
+            // only the selects around the current page are shown when not all of them fit
+            let first_chunk = (self.current_page / OPTIONS_PER_SELECT)
+                .saturating_sub(available_rows / 2)
+                .min(chunks.len().saturating_sub(available_rows));
+
+            if available_rows == 0 {
+                tracing::warn!("No action rows left for the page select");
+            }
+            for (i, chunk) in chunks
+                .into_iter()
+                .enumerate()
+                .skip(first_chunk)
+                .take(available_rows)
+            {
+                let placeholder = if page_count > OPTIONS_PER_SELECT {
+                    format!(
+                        "Jump to page ({}-{})",
+                        chunk[0] + 1,
+                        chunk[chunk.len() - 1] + 1
+                    )
+                } else {
+                    "Jump to page".to_string()
+                };
+                components.create_action_row(|r| {
+                    r.create_select_menu(|s| {
+                        s.custom_id(format!("{}:{}", PAGE_SELECT_ID, i))
+                            .placeholder(placeholder)
+                            .options(|o| {
+                                for page in chunk {
+                                    let label = self
+                                        .get_page_label(*page)
+                                        .chars()
+                                        .take(MAX_OPTION_LABEL_LENGTH)
+                                        .collect::<String>();
+                                    o.create_option(|o| o.label(label).value(page));
+                                }
+                                o
+                            })
+                    })
+                });
+            }
+        }
+
+        components
+    }
+
+    /// Resets the timeout of the menu if it has an idle timeout.
+    /// The timeout never exceeds the deadline of the menu
+    pub fn refresh_timeout(&mut self) {
+        if let Some(idle_timeout) = self.idle_timeout {
+            self.timeout = cap_timeout(Instant::now() + idle_timeout, self.deadline);
+        }
+    }
+
+    /// Runs the given control if the user is permitted to use it
+    /// and returns if the control was run.
+    /// The access policy of the control replaces the one of the menu
+    async fn run_control(
+        &mut self,
+        ctx: &Context,
+        control: ActionContainer,
+        access: Option<AccessPolicy>,
+        event: ControlEvent,
+    ) -> Result<bool> {
+        let access = access.unwrap_or_else(|| self.access.clone());
+
+        if !access.is_permitted(ctx, &event).await? {
+            tracing::debug!("The user is not permitted to use the control");
+            return Ok(false);
+        }
+        self.refresh_timeout();
+        let previous_page = self.current_page;
+        tracing::debug!("Running control");
+        control.run(ctx, self, event).await?;
+
+        if self.current_page != previous_page {
+            if let Some(on_page_change) = self.hooks.on_page_change.clone() {
+                tracing::debug!("Running page change hook");
+                on_page_change(&ctx.http, self).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the control that belongs to the given component custom id
+    fn get_component_control(
+        &self,
+        custom_id: &str,
+    ) -> Option<(ActionContainer, Option<AccessPolicy>)> {
+        if custom_id.starts_with(PAGE_SELECT_ID) {
+            self.page_select.clone().map(|action| (action, None))
+        } else if self.control_mode == ControlMode::Buttons {
+            self.controls
+                .get(custom_id)
+                .map(|c| (c.action.clone(), c.access.clone()))
+        } else {
+            None
+        }
+    }
+
+    /// Handles a component interaction of a menu with private navigation.
+    /// Interactions on the public menu message open a new ephemeral view
+    /// for the user, interactions on a view change only the page of that user
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn handle_private_interaction(
+        &mut self,
+        ctx: &Context,
+        interaction: MessageComponentInteraction,
+    ) -> Result<()> {
+        let handle = self.get_handle().await;
+        let is_public = interaction.message.id.0 == handle.message_id;
+
+        if is_public {
+            tracing::debug!("Acknowledging interaction with a new private view.");
+            interaction
+                .create_interaction_response(&ctx.http, |r| {
+                    r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                        .interaction_response_data(|d| {
+                            d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                        })
+                })
+                .await?;
+        } else {
+            tracing::debug!("Acknowledging interaction.");
+            interaction.defer(&ctx.http).await?;
+        }
+        let user_id = interaction.user.id;
+        let token = interaction.token.clone();
+        let interaction_handle = MessageHandle::new(interaction.channel_id, interaction.message.id);
+        let control = self.get_component_control(&interaction.data.custom_id);
+
+        let public_page = self.current_page;
+        self.current_page = *self.user_pages.get(&user_id).unwrap_or(&public_page);
+        // the help state is swapped the same way as the page so that every view has its own
+        let help = self.data.get::<HelpActiveContainer>().cloned();
+        let public_help = help.as_ref().map(|h| {
+            let user_help = self.user_help.get(&user_id).copied().unwrap_or_default();
+            h.swap(user_help, Ordering::Relaxed)
+        });
+        self.private_view = Some(PrivateView {
+            token: token.clone(),
+            message_id: None,
+            closed: false,
+        });
+        let mut result = match control {
+            Some((action, access)) => {
+                self.run_control(
+                    ctx,
+                    action,
+                    access,
+                    ControlEvent::Component(Box::new(interaction)),
+                )
+                .await
+            }
+            None => Ok(true),
+        };
+        if is_public && matches!(result, Ok(true)) && !self.is_view_displayed() {
+            result = self.display_current_page(&ctx.http).await.map(|_| true);
+        }
+        let view = self.private_view.take();
+        let user_page = mem::replace(&mut self.current_page, public_page);
+        let user_help = match (&help, public_help) {
+            (Some(help), Some(public_help)) => help.swap(public_help, Ordering::Relaxed),
+            _ => false,
+        };
+        let permitted = result?;
+
+        if is_public && !permitted {
+            ctx.http
+                .edit_original_interaction_response(
+                    &token,
+                    &json!({ "content": "You are not permitted to use this menu" }),
+                )
+                .await?;
+        } else if let Some(view) = view {
+            if view.closed {
+                self.user_pages.remove(&user_id);
+                self.user_help.remove(&user_id);
+                if !is_public {
+                    self.listeners.remove(&interaction_handle);
+                }
+                ctx.http
+                    .delete_original_interaction_response(&token)
+                    .await?;
+            } else {
+                self.user_pages.insert(user_id, user_page);
+                self.user_help.insert(user_id, user_help);
+                if let Some(message_id) = view.message_id {
+                    let channel_id = ChannelId(handle.channel_id);
+                    self.register_alias(&handle, MessageHandle::new(channel_id, message_id));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Displays the current page
+    async fn display_current_page(&mut self, http: &Http) -> Result<()> {
+        let page = self.render_current_page().await?;
+        self.display(http, page).await
+    }
+
+    /// Returns if the private view currently handled already displays a page
+    fn is_view_displayed(&self) -> bool {
+        self.private_view
+            .as_ref()
+            .is_some_and(|v| v.message_id.is_some())
+    }
+
+    /// Routes the events of the message with the alias handle to this menu
+    fn register_alias(&self, own_handle: &MessageHandle, alias: MessageHandle) {
+        let menu_ref = self.listeners.get(own_handle).map(|m| m.value().clone());
+
+        if let Some(menu_ref) = menu_ref {
+            self.listeners.insert(alias, menu_ref);
+        }
     }
 }
 
+/// Caps the given timeout at the deadline if there is one
+fn cap_timeout(timeout: Instant, deadline: Option<Instant>) -> Instant {
+    deadline.map_or(timeout, |d| timeout.min(d))
+}
+
+/// Returns the given controls with their keys ordered by their position
+fn sorted_controls(controls: &HashMap<String, Control>) -> Vec<(&String, &Control)> {
+    let mut controls = controls.iter().collect::<Vec<(&String, &Control)>>();
+    controls.sort_by_key(|(_, c)| c.action.position);
+
+    controls
+}
+
 #[async_trait]
 impl<'a> EventDrivenMessage for Menu<'a> {
     fn is_frozen(&self) -> bool {
@@ -164,30 +928,58 @@ impl<'a> EventDrivenMessage for Menu<'a> {
 
     #[tracing::instrument(level = "debug", skip_all)]
     async fn update(&mut self, http: &Http) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
         tracing::trace!("Checking for menu timeout");
 
         if Instant::now() >= self.timeout {
-            tracing::debug!("Menu timout reached. Closing menu.");
-            self.close(http).await?;
-        } else if self.sticky {
-            tracing::debug!("Message is sticky. Checking for new messages in channel...");
+            tracing::debug!("Menu timout reached. Expiring menu.");
+            self.expire(http).await?;
+        } else if self.sticky && self.sticky_debounce.take_due(&self.listeners) {
+            tracing::debug!("New messages in channel. Recreating sticky message...");
+            self.recreate(http).await?;
+        }
 
-            let handle = self.get_handle().await;
-            let channel_id = ChannelId(handle.channel_id);
-            let messages = channel_id
-                .messages(http, |p| p.after(handle.message_id).limit(1))
-                .await?;
-            if messages.len() > 0 {
-                tracing::debug!("New messages in channel. Recreating...");
-                self.recreate(http).await?;
-            }
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, ctx: &Context) -> Result<()> {
+        self.finish(&ctx.http, CloseReason::MessageDeleted).await
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn on_message_create(&mut self, ctx: &Context, message: Message) -> Result<()> {
+        if !self.sticky || self.closed {
+            return Ok(());
         }
+        let handle = self.get_handle().await;
+        let menu = self.listeners.get(&handle).map(|e| e.value().clone());
+        let created = MessageHandle::new(message.channel_id, message.id);
+        self.sticky_debounce
+            .trigger(Arc::clone(&ctx.http), menu, created);
 
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_shutdown(&mut self, ctx: &Context) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        if let Err(e) = self.close(&ctx.http).await {
+            tracing::error!("Failed to remove menu controls on shutdown: {:?}", e);
+        }
+
+        self.finish(&ctx.http, CloseReason::Shutdown).await
+    }
+
     #[tracing::instrument(level = "debug", skip_all)]
     async fn on_reaction_add(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        if self.control_mode != ControlMode::Reactions {
+            return Ok(());
+        }
         let current_user = ctx.http.get_current_user().await?;
         let reaction_user_id = reaction.user_id.ok_or_else(|| Error::NoCache)?;
 
@@ -195,22 +987,45 @@ impl<'a> EventDrivenMessage for Menu<'a> {
             tracing::debug!("Reaction is from current user.");
             return Ok(());
         }
-        let emoji_string = reaction.emoji.as_data();
+        let key = control_key(&reaction.emoji);
 
         tracing::debug!("Deleting user reaction.");
         reaction.delete(ctx).await?;
 
-        if let Some(owner) = self.owner {
-            if owner != reaction_user_id {
-                tracing::debug!(
-                    "Menu has an owner and the reaction is not from the owner of the menu"
-                );
-                return Ok(());
-            }
+        if let Some(control) = self.controls.get(&key).cloned() {
+            self.run_control(
+                ctx,
+                control.action,
+                control.access,
+                ControlEvent::Reaction(Box::new(reaction)),
+            )
+            .await?;
         }
-        if let Some(control) = self.controls.get(&emoji_string).cloned() {
-            tracing::debug!("Running control");
-            control.run(ctx, self, reaction).await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_interaction_create(
+        &mut self,
+        ctx: &Context,
+        interaction: MessageComponentInteraction,
+    ) -> Result<()> {
+        if self.private_navigation {
+            return self.handle_private_interaction(ctx, interaction).await;
+        }
+        tracing::debug!("Acknowledging interaction.");
+        interaction.defer(&ctx.http).await?;
+        let control = self.get_component_control(&interaction.data.custom_id);
+
+        if let Some((action, access)) = control {
+            self.run_control(
+                ctx,
+                action,
+                access,
+                ControlEvent::Component(Box::new(interaction)),
+            )
+            .await?;
         }
 
         Ok(())
@@ -220,26 +1035,52 @@ impl<'a> EventDrivenMessage for Menu<'a> {
 /// A builder for messages
 pub struct MenuBuilder {
     pages: Vec<Page<'static>>,
+    page_source: Option<Arc<dyn PageSource>>,
     current_page: usize,
-    controls: HashMap<String, ActionContainer>,
+    controls: HashMap<String, Control>,
     timeout: Duration,
+    idle_timeout: Option<Duration>,
+    max_lifetime: Option<Duration>,
     sticky: bool,
+    sticky_debounce: Duration,
     data: TypeMap,
     help_entries: HashMap<String, String>,
-    owner: Option<UserId>,
+    access: AccessPolicy,
+    control_mode: ControlMode,
+    page_labels: HashMap<usize, String>,
+    page_indicator: Option<PageIndicator>,
+    expiry_action: ExpiryAction,
+    hooks: MenuHooks,
+    private_navigation: bool,
+    page_select: Option<ActionContainer>,
+    cache_ttl: Option<Duration>,
+    prefetch_distance: usize,
 }
 
 impl Default for MenuBuilder {
     fn default() -> Self {
         Self {
             pages: vec![],
+            page_source: None,
             current_page: 0,
             controls: HashMap::new(),
             timeout: Duration::from_secs(60),
+            idle_timeout: None,
+            max_lifetime: None,
             sticky: false,
+            sticky_debounce: DEFAULT_STICKY_DEBOUNCE,
             data: TypeMap::new(),
             help_entries: HashMap::new(),
-            owner: None,
+            access: AccessPolicy::default(),
+            control_mode: ControlMode::default(),
+            page_labels: HashMap::new(),
+            page_indicator: None,
+            expiry_action: ExpiryAction::default(),
+            hooks: MenuHooks::default(),
+            private_navigation: false,
+            page_select: None,
+            cache_ttl: None,
+            prefetch_distance: 0,
         }
     }
 }
@@ -248,38 +1089,82 @@ impl MenuBuilder {
     /// Creates a new pagination menu
     #[tracing::instrument(level = "debug", skip_all)]
     pub fn new_paginator() -> Self {
-        let mut controls = HashMap::new();
-        let mut help_entries = HashMap::new();
-        controls.insert(
-            PREVIOUS_PAGE_EMOJI.to_string(),
-            ActionContainer::new(0, |c, m, r| previous_page(c, m, r).boxed()),
-        );
-        help_entries.insert(
-            PREVIOUS_PAGE_EMOJI.to_string(),
-            "Displays the previous page".to_string(),
-        );
-        controls.insert(
-            CLOSE_MENU_EMOJI.to_string(),
-            ActionContainer::new(1, |c, m, r| close_menu(c, m, r).boxed()),
-        );
-        help_entries.insert(
-            CLOSE_MENU_EMOJI.to_string(),
-            "Closes the menu buttons".to_string(),
-        );
-        controls.insert(
-            NEXT_PAGE_EMOJI.to_string(),
-            ActionContainer::new(2, |c, m, r| next_page(c, m, r).boxed()),
-        );
-        help_entries.insert(
-            NEXT_PAGE_EMOJI.to_string(),
-            "Displays the next page".to_string(),
-        );
+        Self::default()
+            .add_control(0, PREVIOUS_PAGE_EMOJI, |c, m, r| {
+                previous_page(c, m, r).boxed()
+            })
+            .add_help(PREVIOUS_PAGE_EMOJI, "Displays the previous page")
+            .add_control(1, CLOSE_MENU_EMOJI, |c, m, r| close_menu(c, m, r).boxed())
+            .add_help(CLOSE_MENU_EMOJI, "Closes the menu buttons")
+            .add_control(2, NEXT_PAGE_EMOJI, |c, m, r| next_page(c, m, r).boxed())
+            .add_help(NEXT_PAGE_EMOJI, "Displays the next page")
+    }
 
-        Self {
-            controls,
-            help_entries,
-            ..Default::default()
-        }
+    /// Creates a new pagination menu that additionally has controls
+    /// to jump to the first and last page and to skip multiple pages
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_paginator_extended() -> Self {
+        Self::new_paginator()
+            .first_last_controls()
+            .skip_controls(DEFAULT_SKIP_AMOUNT)
+    }
+
+    /// Creates a new pagination menu that displays the given text.
+    /// The text is split into pages that stay within the length limits of the given style
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_text_paginator<S: AsRef<str>>(text: S, style: TextPageStyle) -> Self {
+        Self::new_paginator().add_pages(text_pages(text.as_ref(), style))
+    }
+
+    /// Creates the page cache if caching or prefetching is enabled
+    fn create_page_cache(&self) -> Option<PageCache> {
+        (self.cache_ttl.is_some() || self.prefetch_distance > 0).then(|| {
+            PageCache::new(
+                &self.pages,
+                self.cache_ttl.unwrap_or(DEFAULT_PAGE_CACHE_TTL),
+                self.prefetch_distance,
+            )
+        })
+    }
+
+    /// Creates a new pagination menu that displays the given item list
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_list_paginator<T>(list: ItemList<T>) -> Self {
+        Self::new_paginator().add_pages(list.build_pages())
+    }
+
+    /// Creates a new pagination menu that displays the given table
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_table_paginator(table: Table) -> Self {
+        Self::new_paginator().add_pages(table.build_pages())
+    }
+
+    /// Adds controls to jump to the first and the last page
+    pub fn first_last_controls(self) -> Self {
+        self.add_control(-2, FIRST_PAGE_EMOJI, |c, m, r| first_page(c, m, r).boxed())
+            .add_help(FIRST_PAGE_EMOJI, "Displays the first page")
+            .add_control(4, LAST_PAGE_EMOJI, |c, m, r| last_page(c, m, r).boxed())
+            .add_help(LAST_PAGE_EMOJI, "Displays the last page")
+    }
+
+    /// Adds controls to skip the given amount of pages forward or backward
+    pub fn skip_controls(self, amount: usize) -> Self {
+        let amount = amount as isize;
+
+        self.add_control(-1, SKIP_BACKWARD_EMOJI, move |c, m, _| {
+            skip_pages(c, m, -amount).boxed()
+        })
+        .add_help(
+            SKIP_BACKWARD_EMOJI.to_string(),
+            format!("Goes back {} pages", amount),
+        )
+        .add_control(3, SKIP_FORWARD_EMOJI, move |c, m, _| {
+            skip_pages(c, m, amount).boxed()
+        })
+        .add_help(
+            SKIP_FORWARD_EMOJI.to_string(),
+            format!("Goes forward {} pages", amount),
+        )
     }
 
     /// Adds a page to the message builder
@@ -289,6 +1174,14 @@ impl MenuBuilder {
         self
     }
 
+    /// Adds a page with a label that is displayed in the page select
+    pub fn add_labeled_page<S: ToString>(mut self, label: S, page: Page<'static>) -> Self {
+        self.page_labels.insert(self.pages.len(), label.to_string());
+        self.pages.push(page);
+
+        self
+    }
+
     /// Adds multiple pages to the message
     pub fn add_pages<I>(mut self, pages: I) -> Self
     where
@@ -300,20 +1193,37 @@ impl MenuBuilder {
         self
     }
 
-    /// Adds a single control to the message
-    pub fn add_control<S, F: 'static>(mut self, position: isize, emoji: S, action: F) -> Self
+    /// Adds a single control to the message.
+    /// The emoji can be a unicode emoji, a [ReactionType] or
+    /// a custom emoji string like `<:name:id>` or `<a:name:id>`
+    pub fn add_control<S, F>(mut self, position: isize, emoji: S, action: F) -> Self
     where
         S: ToString,
-        F: for<'b> Fn(&'b Context, &'b mut Menu<'_>, Reaction) -> ControlActionResult<'b>
+        F: for<'b> Fn(&'b Context, &'b mut Menu<'_>, ControlEvent) -> ControlActionResult<'b>
             + Send
-            + Sync,
+            + Sync
+            + 'static,
     {
-        self.controls
-            .insert(emoji.to_string(), ActionContainer::new(position, action));
+        let control = Control::new(emoji, ActionContainer::new(position, action));
+        self.controls.insert(control_key(&control.emoji), control);
 
         self
     }
 
+    /// Adds a control that opens the menu created by the given function as a submenu
+    pub fn add_submenu<S, F>(self, position: isize, emoji: S, submenu: F) -> Self
+    where
+        S: ToString,
+        F: Fn() -> MenuBuilder + Send + Sync + 'static,
+    {
+        let submenu = Arc::new(submenu);
+
+        self.add_control(position, emoji, move |c, m, _| {
+            let submenu = Arc::clone(&submenu);
+            async move { m.open_submenu(&c.http, submenu()).await }.boxed()
+        })
+    }
+
     /// Adds a single control to the message
     pub fn add_controls<S, I>(mut self, controls: I) -> Self
     where
@@ -321,18 +1231,44 @@ impl MenuBuilder {
         I: IntoIterator<Item = (isize, S, ControlActionArc)>,
     {
         for (position, emoji, action) in controls {
-            self.controls.insert(
-                emoji.to_string(),
+            let control = Control::new(
+                emoji,
                 ActionContainer {
                     position,
                     inner: action,
                 },
             );
+            self.controls.insert(control_key(&control.emoji), control);
         }
 
         self
     }
 
+    /// Sets a source that creates pages lazily when they are accessed.
+    /// The source replaces all pages added to the builder
+    pub fn page_source<S: PageSource + 'static>(mut self, source: S) -> Self {
+        self.page_source = Some(Arc::new(source));
+
+        self
+    }
+
+    /// Caches the output of builder pages for the given duration
+    /// instead of running the builder on every access
+    pub fn cache_pages(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+
+        self
+    }
+
+    /// Builds the given number of pages before and after the current page
+    /// in the background. Enables caching with the default ttl if
+    /// caching hasn't been configured
+    pub fn prefetch_pages(mut self, distance: usize) -> Self {
+        self.prefetch_distance = distance;
+
+        self
+    }
+
     /// Sets the timeout for the message
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
@@ -340,6 +1276,22 @@ impl MenuBuilder {
         self
     }
 
+    /// Sets an inactivity timeout for the message that is reset
+    /// every time a control is used. Replaces the fixed [Self::timeout]
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the maximum lifetime of the message after which it
+    /// expires regardless of the (idle) timeout
+    pub fn max_lifetime(mut self, lifetime: Duration) -> Self {
+        self.max_lifetime = Some(lifetime);
+
+        self
+    }
+
     /// Sets the start page of the message
     pub fn start_page(mut self, page: usize) -> Self {
         self.current_page = page;
@@ -355,6 +1307,14 @@ impl MenuBuilder {
         self
     }
 
+    /// Sets the time without new messages in the channel
+    /// before a sticky message is recreated
+    pub fn sticky_debounce(mut self, debounce: Duration) -> Self {
+        self.sticky_debounce = debounce;
+
+        self
+    }
+
     /// Adds data to the menu typemap
     pub fn add_data<T>(mut self, value: T::Value) -> Self
     where
@@ -368,7 +1328,7 @@ impl MenuBuilder {
     /// Adds a help entry
     pub fn add_help<S: ToString>(mut self, button: S, help: S) -> Self {
         self.help_entries
-            .insert(button.to_string(), help.to_string());
+            .insert(control_key(&parse_control_emoji(button)), help.to_string());
 
         self
     }
@@ -379,10 +1339,118 @@ impl MenuBuilder {
             .add_data::<HelpActiveContainer>(Arc::new(AtomicBool::new(false)))
     }
 
+    /// Shows a select menu that lists all pages
+    /// and allows jumping to a page directly.
+    /// Menus with more than 25 pages get one select menu per 25 pages.
+    /// When the selects don't fit into the action rows left by the buttons,
+    /// only the selects around the current page are shown
+    pub fn show_page_select(mut self) -> Self {
+        self.page_select = Some(ActionContainer::new(0, |c, m, e| {
+            jump_to_page(c, m, e).boxed()
+        }));
+
+        self
+    }
+
+    /// Sets what happens to the menu when its timeout is reached.
+    /// Defaults to [ExpiryAction::RemoveControls]
+    pub fn expiry_action(mut self, action: ExpiryAction) -> Self {
+        self.expiry_action = action;
+
+        self
+    }
+
+    /// Gives every user that interacts with the menu their own ephemeral view
+    /// with an independent current page. The public menu message keeps
+    /// showing the start page. The menu always uses [ControlMode::Buttons]
+    /// regardless of the configured control mode.
+    /// Views can only be updated while the interaction token is valid (15 minutes)
+    pub fn private_navigation(mut self, value: bool) -> Self {
+        self.private_navigation = value;
+
+        self
+    }
+
+    /// Sets a hook that is run once the menu message has been created
+    pub fn on_open<F>(mut self, hook: F) -> Self
+    where
+        F: for<'b> Fn(&'b Http, &'b mut Menu<'_>) -> ControlActionResult<'b>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.on_open = Some(Arc::new(hook));
+
+        self
+    }
+
+    /// Sets a hook that is run every time a control changes the current page
+    pub fn on_page_change<F>(mut self, hook: F) -> Self
+    where
+        F: for<'b> Fn(&'b Http, &'b mut Menu<'_>) -> ControlActionResult<'b>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.on_page_change = Some(Arc::new(hook));
+
+        self
+    }
+
+    /// Sets a hook that is run once when the menu is closed
+    pub fn on_close<F>(mut self, hook: F) -> Self
+    where
+        F: for<'b> Fn(&'b Http, &'b mut Menu<'_>, CloseReason) -> ControlActionResult<'b>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.on_close = Some(Arc::new(hook));
+
+        self
+    }
+
+    /// Stamps the position of the current page into every displayed page
+    pub fn page_indicator(mut self, indicator: PageIndicator) -> Self {
+        self.page_indicator = Some(indicator);
+
+        self
+    }
+
     /// Sets the owner of the menu
     /// if it's set only the owner can interact with the menu
-    pub fn owner(mut self, user_id: UserId) -> Self {
-        self.owner = Some(user_id);
+    pub fn owner(self, user_id: UserId) -> Self {
+        self.access_policy(AccessPolicy::users(vec![user_id]))
+    }
+
+    /// Sets the policy that decides who can use the controls of the menu
+    pub fn access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access = policy;
+
+        self
+    }
+
+    /// Sets the policy that decides who can use the control with the given emoji.
+    /// It replaces the access policy of the menu for this control.
+    /// The control needs to be added before
+    pub fn control_access<S: ToString>(mut self, emoji: S, policy: AccessPolicy) -> Self {
+        let key = control_key(&parse_control_emoji(emoji));
+
+        if let Some(control) = self.controls.get_mut(&key) {
+            control.access = Some(policy);
+        } else {
+            tracing::warn!("No control for the emoji found. Ignoring access policy");
+        }
+
+        self
+    }
+
+    /// Sets how the controls of the menu are displayed.
+    /// With [ControlMode::Buttons] the controls are rendered as
+    /// message buttons instead of reactions.
+    /// Ignored when [Self::private_navigation] is enabled
+    pub fn control_mode(mut self, mode: ControlMode) -> Self {
+        self.control_mode = mode;
 
         self
     }
@@ -394,60 +1462,106 @@ impl MenuBuilder {
         ctx: &Context,
         channel_id: ChannelId,
     ) -> Result<Arc<RwLock<MessageHandle>>> {
-        let mut current_page = self
-            .pages
-            .get(self.current_page)
-            .ok_or(Error::PageNotFound(self.current_page))?
-            .clone()
-            .get()
-            .await?;
-        tracing::trace!("current_page = {:?}", current_page);
-
-        let message = channel_id
-            .send_message(ctx, |msg| {
-                mem::swap(msg, &mut current_page);
-                msg
-            })
-            .await?;
-        tracing::trace!("message = {:?}", current_page);
+        let handle = self.build_with_handle(ctx, channel_id).await?;
 
-        tracing::debug!("Sorting controls...");
-        let mut controls = self
-            .controls
-            .clone()
-            .into_iter()
-            .collect::<Vec<(String, ActionContainer)>>();
-        controls.sort_by_key(|(_, a)| a.position);
+        Ok(handle.message())
+    }
 
+    /// Builds the menu and returns a handle that can be awaited until the menu is closed
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build_with_handle(
+        self,
+        ctx: &Context,
+        channel_id: ChannelId,
+    ) -> Result<MenuHandle> {
         tracing::debug!("Creating menu...");
-        let message_handle = MessageHandle::new(message.channel_id, message.id);
-        let handle_lock = Arc::new(RwLock::new(message_handle));
+        let (completion, receiver) = oneshot::channel();
+        let now = Instant::now();
+        let deadline = self.max_lifetime.map(|l| now + l);
+        let timeout = now + self.idle_timeout.unwrap_or(self.timeout);
+        let handle_lock = Arc::new(RwLock::new(MessageHandle::from_raw_ids(channel_id.0, 0)));
         let listeners = get_listeners_from_context(ctx).await?;
+        let page_cache = self.create_page_cache();
+        // private views are only possible with interactions
+        let control_mode = if self.private_navigation {
+            if self.control_mode != ControlMode::Buttons {
+                tracing::warn!("Private navigation requires buttons. Ignoring the control mode");
+            }
+            ControlMode::Buttons
+        } else {
+            self.control_mode
+        };
 
-        let menu = Menu {
+        let mut menu = Menu {
             message: Arc::clone(&handle_lock),
             pages: self.pages,
+            page_source: self.page_source,
             current_page: self.current_page,
             controls: self.controls,
-            timeout: Instant::now() + self.timeout,
+            timeout: cap_timeout(timeout, deadline),
+            idle_timeout: self.idle_timeout,
+            deadline,
+            result: None,
+            completion: Some(completion),
             closed: false,
             listeners: Arc::clone(&listeners),
             sticky: self.sticky,
+            sticky_debounce: StickyDebounce::new(self.sticky_debounce),
             data: self.data,
             help_entries: self.help_entries,
-            owner: self.owner,
+            access: self.access,
+            control_mode,
+            page_labels: self.page_labels,
+            page_indicator: self.page_indicator,
+            expiry_action: self.expiry_action,
+            hooks: self.hooks,
+            private_navigation: self.private_navigation,
+            user_pages: HashMap::new(),
+            user_help: HashMap::new(),
+            private_view: None,
+            page_select: self.page_select,
+            page_cache,
+            parents: Vec::new(),
         };
+        let mut current_page = menu.render_current_page().await?;
+        tracing::trace!("current_page = {:?}", current_page);
+
+        if menu.has_components() {
+            current_page.set_components(menu.create_components());
+        }
+        let message = channel_id
+            .send_message(ctx, |msg| {
+                mem::swap(msg, &mut current_page);
+                msg
+            })
+            .await?;
+        tracing::trace!("message = {:?}", current_page);
+
+        let message_handle = MessageHandle::new(message.channel_id, message.id);
+        *handle_lock.write().await = message_handle;
+
+        if let Some(on_open) = menu.hooks.on_open.clone() {
+            tracing::debug!("Running open hook");
+            on_open(&ctx.http, &mut menu).await?;
+        }
+
+        tracing::debug!("Sorting controls...");
+        let controls = sorted_controls(&menu.controls)
+            .into_iter()
+            .map(|(_, c)| c.emoji.clone())
+            .collect::<Vec<ReactionType>>();
+        let control_mode = menu.control_mode;
 
         tracing::debug!("Storing menu to listeners...");
         listeners.insert(message_handle, Arc::new(Mutex::new(Box::new(menu).into())));
 
-        tracing::debug!("Adding controls...");
-        for (emoji, _) in controls {
-            message
-                .react(ctx, ReactionType::Unicode(emoji.clone()))
-                .await?;
+        if control_mode == ControlMode::Reactions {
+            tracing::debug!("Adding controls...");
+            for emoji in controls {
+                message.react(ctx, emoji).await?;
+            }
         }
 
-        Ok(handle_lock)
+        Ok(MenuHandle::new(handle_lock, receiver))
     }
 }
@@ -1,16 +1,35 @@
+pub(crate) mod access;
+pub(crate) mod cache;
+pub(crate) mod confirm;
 pub(crate) mod container;
 pub(crate) mod controls;
+pub(crate) mod handle;
+pub(crate) mod hooks;
+pub(crate) mod list;
+#[allow(clippy::module_inception)]
 pub(crate) mod menu;
 pub(crate) mod page;
+pub(crate) mod table;
+pub(crate) mod text;
 pub(crate) mod traits;
 pub(crate) mod typedata;
 
+pub use access::*;
+pub use cache::DEFAULT_PAGE_CACHE_TTL;
+pub use confirm::*;
 pub use container::*;
 pub use controls::*;
+pub use handle::*;
+pub use hooks::*;
+pub use list::*;
 pub use menu::{
-    ActionContainer, ControlActionArc, Menu, MenuBuilder, CLOSE_MENU_EMOJI, NEXT_PAGE_EMOJI,
-    PREVIOUS_PAGE_EMOJI,
+    ActionContainer, ControlActionArc, ControlEvent, ControlMode, ExpiryAction, Menu, MenuBuilder,
+    BACK_EMOJI, CLOSE_MENU_EMOJI, DEFAULT_SKIP_AMOUNT, FIRST_PAGE_EMOJI, HELP_EMOJI,
+    LAST_PAGE_EMOJI, NEXT_PAGE_EMOJI, PAGE_SELECT_ID, PREVIOUS_PAGE_EMOJI, SKIP_BACKWARD_EMOJI,
+    SKIP_FORWARD_EMOJI,
 };
 pub use page::*;
+pub use table::*;
+pub use text::*;
 
 pub use traits::EventDrivenMessage;
@@ -1,4 +1,7 @@
 use crate::error::Result;
+use crate::menu::text::{truncate, MAX_CONTENT_LENGTH};
+use serde_json::{json, Value};
+use serenity::async_trait;
 use serenity::builder::CreateMessage;
 use std::future::Future;
 use std::pin::Pin;
@@ -19,9 +22,9 @@ pub enum Page<'b> {
 impl<'b> Page<'b> {
     /// Creates a new page with the given builder function that creates a page
     /// each time it is accessed
-    pub fn new_builder<F: 'static>(builder_fn: F) -> Self
+    pub fn new_builder<F>(builder_fn: F) -> Self
     where
-        F: Fn() -> MessageBuildOutput<'b> + Send + Sync,
+        F: Fn() -> MessageBuildOutput<'b> + Send + Sync + 'static,
     {
         Self::Builder(Arc::new(builder_fn))
     }
@@ -39,3 +42,170 @@ impl<'b> Page<'b> {
         }
     }
 }
+
+impl Page<'static> {
+    /// Converts the page into a page with a shorter lifetime
+    pub(crate) fn into_bounded<'a>(self) -> Page<'a> {
+        match self {
+            Page::Builder(b) => Page::Builder(Arc::new(move || -> MessageBuildOutput<'a> {
+                let page = b();
+                Box::pin(async move {
+                    let message: CreateMessage<'a> = page.await?;
+                    Ok(message)
+                })
+            })),
+            Page::Static(inner) => Page::Static(inner),
+        }
+    }
+}
+
+/// A source of pages that are created lazily when they are accessed.
+/// Allows browsing page lists that are unbounded or too expensive
+/// to create up front like search results or paged API responses
+#[async_trait]
+pub trait PageSource: Send + Sync {
+    /// Returns the page with the given index or `None` if the page doesn't exist
+    async fn get(&self, index: usize) -> Result<Option<CreateMessage<'static>>>;
+
+    /// Returns the number of pages if it is known
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns if the source is known to have no pages
+    fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+}
+
+/// The default format of the page indicator
+pub static DEFAULT_PAGE_INDICATOR_FORMAT: &str = "Page {page}/{total}";
+/// Separates an existing footer text from the page indicator
+static FOOTER_SEPARATOR: &str = " • ";
+
+#[derive(Clone, Debug)]
+/// Stamps the position of the current page into the rendered page.
+/// The format string may contain `{page}` for the one-based page number
+/// and `{total}` for the number of pages (`?` if it's unknown)
+pub enum PageIndicator {
+    /// Appends the indicator to the footer text of the last embed of the page.
+    /// Pages without an embed get the indicator appended to the content instead
+    Footer(String),
+    /// Appends the indicator to the content of the page.
+    /// The content is shortened when it would exceed the maximum length otherwise
+    Content(String),
+}
+
+impl Default for PageIndicator {
+    fn default() -> Self {
+        Self::Footer(DEFAULT_PAGE_INDICATOR_FORMAT.to_string())
+    }
+}
+
+impl PageIndicator {
+    /// Returns the formatted indicator text for the given page
+    pub fn format(&self, page: usize, total: Option<usize>) -> String {
+        let format = match self {
+            PageIndicator::Footer(f) | PageIndicator::Content(f) => f,
+        };
+        let total = total.map_or_else(|| "?".to_string(), |t| t.to_string());
+
+        format
+            .replace("{page}", &(page + 1).to_string())
+            .replace("{total}", &total)
+    }
+
+    /// Stamps the indicator into the given message
+    pub fn apply(&self, message: &mut CreateMessage<'_>, page: usize, total: Option<usize>) {
+        let text = self.format(page, total);
+
+        if let PageIndicator::Footer(_) = self {
+            let embed = message
+                .0
+                .get_mut("embeds")
+                .and_then(Value::as_array_mut)
+                .and_then(|embeds| embeds.last_mut())
+                .and_then(Value::as_object_mut);
+
+            if let Some(embed) = embed {
+                let footer = embed
+                    .entry("footer")
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut();
+
+                if let Some(footer) = footer {
+                    let text = match footer.get("text").and_then(Value::as_str) {
+                        Some(existing) if !existing.is_empty() => {
+                            format!("{}{}{}", existing, FOOTER_SEPARATOR, text)
+                        }
+                        _ => text,
+                    };
+                    footer.insert("text".to_string(), Value::String(text));
+                } else {
+                    embed.insert("footer".to_string(), json!({ "text": text }));
+                }
+                return;
+            }
+        }
+        let text = truncate(&text, MAX_CONTENT_LENGTH);
+        let content = match message.0.get("content").and_then(Value::as_str) {
+            Some(content) if !content.is_empty() => {
+                // the content is shortened if the indicator wouldn't fit otherwise
+                let max_length = MAX_CONTENT_LENGTH.saturating_sub(text.chars().count() + 1);
+                format!("{}\n{}", truncate(content, max_length), text)
+            }
+            _ => text,
+        };
+        message.content(content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::text::{text_pages, TextPageStyle};
+
+    fn content(message: &CreateMessage<'_>) -> String {
+        message.0["content"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn full_content_pages_stay_within_the_limit() {
+        let mut message = CreateMessage::default();
+        message.content("a".repeat(MAX_CONTENT_LENGTH));
+        PageIndicator::default().apply(&mut message, 0, Some(3));
+        let content = content(&message);
+
+        assert_eq!(content.chars().count(), MAX_CONTENT_LENGTH);
+        assert!(content.ends_with("…\nPage 1/3"));
+    }
+
+    #[test]
+    fn text_pages_leave_room_for_the_indicator() {
+        let pages = text_pages(&"word ".repeat(2000), TextPageStyle::Content);
+        let mut message = match &pages[0] {
+            Page::Static(message) => message.clone(),
+            Page::Builder(_) => unreachable!(),
+        };
+        let text = content(&message);
+        PageIndicator::default().apply(&mut message, 0, Some(pages.len()));
+
+        assert_eq!(
+            content(&message),
+            format!("{}\nPage 1/{}", text, pages.len())
+        );
+        assert!(content(&message).chars().count() <= MAX_CONTENT_LENGTH);
+    }
+
+    #[test]
+    fn footer_indicator_is_appended_to_existing_footers() {
+        let mut message = CreateMessage::default();
+        message.embed(|e| e.footer(|f| f.text("footer")));
+        PageIndicator::default().apply(&mut message, 1, None);
+
+        assert_eq!(
+            message.0["embeds"][0]["footer"]["text"],
+            "footer • Page 2/?"
+        );
+    }
+}
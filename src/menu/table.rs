@@ -0,0 +1,265 @@
+use crate::menu::page::Page;
+use crate::menu::text::{truncate, MAX_CONTENT_LENGTH};
+use serenity::builder::CreateMessage;
+
+static COLUMN_SEPARATOR: &str = " | ";
+static HEADER_SEPARATOR: &str = "-+-";
+const CODE_FENCE_LENGTH: usize = 3;
+
+/// The alignment of the cells in a table column
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Renders rows as an aligned monospace table inside of code blocks.
+/// Rows are split across multiple pages when the table exceeds the maximum length of a page.
+/// The header is repeated on every page
+#[derive(Clone, Debug)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    alignments: Vec<ColumnAlignment>,
+    title: Option<String>,
+    max_length: usize,
+}
+
+impl Table {
+    /// Creates a new table with the given column headers
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        Self {
+            headers: headers
+                .into_iter()
+                .map(|h| clean_cell(h.to_string()))
+                .collect(),
+            rows: Vec::new(),
+            alignments: Vec::new(),
+            title: None,
+            max_length: MAX_CONTENT_LENGTH,
+        }
+    }
+
+    /// Adds a row to the table
+    pub fn add_row<I, S>(mut self, row: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        self.rows
+            .push(row.into_iter().map(|c| clean_cell(c.to_string())).collect());
+
+        self
+    }
+
+    /// Adds multiple rows to the table
+    pub fn add_rows<I, R, S>(self, rows: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        rows.into_iter().fold(self, |table, row| table.add_row(row))
+    }
+
+    /// Sets the alignment of the column with the given index
+    pub fn align(mut self, column: usize, alignment: ColumnAlignment) -> Self {
+        if self.alignments.len() <= column {
+            self.alignments
+                .resize(column + 1, ColumnAlignment::default());
+        }
+        self.alignments[column] = alignment;
+
+        self
+    }
+
+    /// Sets a title that is displayed above the table on every page
+    pub fn title<S: ToString>(mut self, title: S) -> Self {
+        self.title = Some(clean_cell(title.to_string()));
+
+        self
+    }
+
+    /// Sets the maximum number of characters of a page
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+
+        self
+    }
+
+    /// Renders the table into one or more texts that stay within the maximum length.
+    /// Lines that are too wide for the header and a single row to fit on a page are truncated
+    pub fn render(&self) -> Vec<String> {
+        let title = self
+            .title
+            .as_ref()
+            .map(|t| truncate(t, self.max_length / 4))
+            .map(|t| format!("{}\n", t))
+            .unwrap_or_default();
+        // the title, the code fences and the header, separator and one row with line breaks
+        let line_limit = (self
+            .max_length
+            .saturating_sub(char_count(&title) + CODE_FENCE_LENGTH * 2 + 4)
+            / 3)
+        .max(1);
+        let widths = self.column_widths();
+        let header = truncate(
+            self.render_row(&self.headers, &widths).trim_end(),
+            line_limit,
+        );
+        let separator = widths
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<String>>()
+            .join(HEADER_SEPARATOR);
+        let separator = truncate(&separator, line_limit);
+
+        let mut prefix = title;
+        prefix.push_str("```\n");
+        prefix.push_str(&header);
+        prefix.push('\n');
+        prefix.push_str(&separator);
+        prefix.push('\n');
+        let suffix = "```";
+
+        let base_length = char_count(&prefix) + suffix.len();
+        let mut pages = Vec::new();
+        let mut page_rows: Vec<String> = Vec::new();
+        let mut page_length = base_length;
+
+        for row in &self.rows {
+            let mut line = truncate(self.render_row(row, &widths).trim_end(), line_limit);
+            line.push('\n');
+            let line_length = char_count(&line);
+
+            if !page_rows.is_empty() && page_length + line_length > self.max_length {
+                pages.push(format!("{}{}{}", prefix, page_rows.concat(), suffix));
+                page_rows.clear();
+                page_length = base_length;
+            }
+            page_length += line_length;
+            page_rows.push(line);
+        }
+        if !page_rows.is_empty() || pages.is_empty() {
+            pages.push(format!("{}{}{}", prefix, page_rows.concat(), suffix));
+        }
+
+        pages
+    }
+
+    /// Creates the pages of the table
+    pub fn build_pages(&self) -> Vec<Page<'static>> {
+        self.render()
+            .into_iter()
+            .map(|content| {
+                let mut message = CreateMessage::default();
+                message.content(content);
+
+                Page::new_static(message)
+            })
+            .collect()
+    }
+
+    fn column_widths(&self) -> Vec<usize> {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.headers.len()])
+            .max()
+            .unwrap_or_default();
+        let mut widths = vec![0; columns];
+
+        for row in self.rows.iter().chain([&self.headers]) {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(char_count(cell));
+            }
+        }
+
+        widths
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+        widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| {
+                let cell = row.get(i).map(String::as_str).unwrap_or_default();
+                let padding = " ".repeat(width - char_count(cell));
+
+                match self.alignments.get(i).copied().unwrap_or_default() {
+                    ColumnAlignment::Left => format!("{}{}", cell, padding),
+                    ColumnAlignment::Right => format!("{}{}", padding, cell),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(COLUMN_SEPARATOR)
+    }
+}
+
+/// Removes characters from a cell that would break the table layout
+fn clean_cell(cell: String) -> String {
+    cell.replace(['\n', '\r'], " ").replace('`', "'")
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_aligned_columns() {
+        let pages = Table::new(["Name", "Count"])
+            .add_row(["apple", "3"])
+            .add_row(["kiwi", "12"])
+            .align(1, ColumnAlignment::Right)
+            .render();
+
+        assert_eq!(
+            pages,
+            vec!["```\nName  | Count\n------+------\napple |     3\nkiwi  |    12\n```"]
+        );
+    }
+
+    #[test]
+    fn headers_and_title_are_sanitized() {
+        let pages = Table::new(["a\nb", "```"])
+            .title("title```")
+            .add_row(["1", "2"])
+            .render();
+
+        assert_eq!(pages[0].matches("```").count(), 2);
+        assert!(pages[0].contains("a b"));
+    }
+
+    #[test]
+    fn pages_never_exceed_the_maximum_length() {
+        let table = Table::new(["id", "text"])
+            .title("A table")
+            .max_length(100)
+            .add_rows((0..20).map(|i| vec![i.to_string(), "x".repeat(i * 10)]));
+        let pages = table.render();
+
+        assert!(pages.len() > 1);
+        for page in pages {
+            assert!(char_count(&page) <= 100, "{:?}", page);
+        }
+    }
+
+    #[test]
+    fn wide_headers_are_truncated() {
+        let table = Table::new(["h".repeat(3000)]).add_row(["row"]).render();
+
+        for page in table {
+            assert!(char_count(&page) <= MAX_CONTENT_LENGTH);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use crate::menu::page::Page;
+use serenity::builder::CreateMessage;
+use std::mem;
+
+/// The maximum number of characters in the content of a message
+pub static MAX_CONTENT_LENGTH: usize = 2000;
+/// The maximum number of characters in the description of an embed
+pub static MAX_EMBED_DESCRIPTION_LENGTH: usize = 4096;
+/// The maximum number of characters in the title of an embed
+pub static MAX_EMBED_TITLE_LENGTH: usize = 256;
+/// The maximum number of characters in the value of an embed field
+pub static MAX_EMBED_FIELD_VALUE_LENGTH: usize = 1024;
+/// The maximum number of characters in the footer of an embed
+pub static MAX_EMBED_FOOTER_LENGTH: usize = 2048;
+/// The maximum number of characters of all texts of an embed combined
+pub static MAX_EMBED_LENGTH: usize = 6000;
+
+static CODE_FENCE: &str = "```";
+/// Displayed on pages without text because discord rejects empty messages
+static EMPTY_PAGE_TEXT: &str = "\u{200b}";
+/// The number of characters left free on content pages for a page indicator
+pub static PAGE_INDICATOR_RESERVE: usize = 32;
+
+/// Where the text of a text page is placed
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TextPageStyle {
+    /// The text is the content of the message
+    #[default]
+    Content,
+    /// The text is the description of an embed
+    EmbedDescription,
+}
+
+impl TextPageStyle {
+    /// Returns the maximum number of characters a page of this style can hold
+    pub fn max_length(&self) -> usize {
+        match self {
+            TextPageStyle::Content => MAX_CONTENT_LENGTH,
+            TextPageStyle::EmbedDescription => MAX_EMBED_DESCRIPTION_LENGTH,
+        }
+    }
+
+    /// Creates a page with the given text
+    pub fn create_page(&self, mut text: String) -> Page<'static> {
+        if text.trim().is_empty() {
+            text = EMPTY_PAGE_TEXT.to_string();
+        }
+        let mut message = CreateMessage::default();
+        match self {
+            TextPageStyle::Content => {
+                message.content(text);
+            }
+            TextPageStyle::EmbedDescription => {
+                message.embed(|e| e.description(text));
+            }
+        }
+
+        Page::new_static(message)
+    }
+}
+
+/// Splits the given text into pages of the given style.
+/// Content pages leave room for a page indicator
+pub fn text_pages(text: &str, style: TextPageStyle) -> Vec<Page<'static>> {
+    let max_length = match style {
+        TextPageStyle::Content => style.max_length() - PAGE_INDICATOR_RESERVE,
+        TextPageStyle::EmbedDescription => style.max_length(),
+    };
+
+    split_text(text, max_length)
+        .into_iter()
+        .map(|t| style.create_page(t))
+        .collect()
+}
+
+/// Splits the given text into chunks of at most `max_length` characters.
+/// Chunks end at line breaks where possible and at spaces otherwise.
+/// Code fences that span multiple chunks are closed at the end of a chunk
+/// and reopened at the start of the next one.
+/// Always returns at least one chunk
+pub fn split_text(text: &str, max_length: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = TextChunk::default();
+    let mut fence: Option<String> = None;
+
+    for line in text.split_inclusive('\n') {
+        let toggles_fence = line.matches(CODE_FENCE).count() % 2 == 1;
+        let opener = fence.clone().or_else(|| {
+            toggles_fence.then(|| {
+                let trimmed = line.trim();
+                if trimmed.starts_with(CODE_FENCE) {
+                    trimmed.to_string()
+                } else {
+                    CODE_FENCE.to_string()
+                }
+            })
+        });
+        let overhead = opener.as_ref().map_or(0, |o| fence_overhead(o));
+        let closes_fence = fence.is_some() && toggles_fence;
+        // a line that closes the fence doesn't need room for another closing fence
+        let closing_length = if opener.is_some() && !closes_fence {
+            CODE_FENCE.len() + 1
+        } else {
+            0
+        };
+
+        for piece in split_line(line, max_length.saturating_sub(overhead).max(1)) {
+            if chunk.length > 0 && chunk.length + char_count(&piece) + closing_length > max_length {
+                // chunks with only whitespace are dropped instead of becoming empty pages
+                if chunk.has_text {
+                    chunks.push(chunk.finish(fence.is_some()));
+                }
+                chunk = TextChunk::default();
+
+                // the fence was already closed at the end of the previous chunk
+                if closes_fence && piece.trim() == CODE_FENCE {
+                    continue;
+                }
+                if let Some(opener) = &fence {
+                    chunk.push(opener);
+                    chunk.push("\n");
+                }
+            }
+            chunk.push(&piece);
+            chunk.has_text |= !piece.trim().is_empty();
+        }
+        if toggles_fence {
+            fence = match fence {
+                Some(_) => None,
+                None => opener,
+            };
+        }
+    }
+    if chunk.has_text || chunks.is_empty() {
+        chunks.push(chunk.finish(fence.is_some()));
+    }
+
+    chunks
+}
+
+#[derive(Default)]
+struct TextChunk {
+    text: String,
+    length: usize,
+    has_text: bool,
+}
+
+impl TextChunk {
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.length += char_count(text);
+    }
+
+    fn finish(self, close_fence: bool) -> String {
+        let mut text = self.text.trim_end().to_string();
+
+        if close_fence {
+            text.push('\n');
+            text.push_str(CODE_FENCE);
+        }
+
+        text
+    }
+}
+
+/// Splits a line that is too long into pieces at spaces
+/// and splits single words that are still too long
+fn split_line(line: &str, max_length: usize) -> Vec<String> {
+    if char_count(line) <= max_length {
+        return vec![line.to_string()];
+    }
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut piece_length = 0;
+
+    for word in line.split_inclusive(' ') {
+        let word_length = char_count(word);
+
+        if piece_length + word_length > max_length && !piece.is_empty() {
+            pieces.push(mem::take(&mut piece));
+            piece_length = 0;
+        }
+        if word_length > max_length {
+            let chars = word.chars().collect::<Vec<char>>();
+            let mut parts = chars
+                .chunks(max_length)
+                .map(|c| c.iter().collect::<String>());
+            let last = parts.next_back();
+            pieces.extend(parts);
+
+            if let Some(last) = last {
+                piece_length = char_count(&last);
+                piece = last;
+            }
+        } else {
+            piece.push_str(word);
+            piece_length += word_length;
+        }
+    }
+    if !piece.is_empty() {
+        pieces.push(piece);
+    }
+
+    pieces
+}
+
+/// The number of characters needed to reopen and close a code fence in a chunk
+fn fence_overhead(opener: &str) -> usize {
+    char_count(opener) + CODE_FENCE.len() + 2
+}
+
+/// Shortens the text to at most `max_length` characters and marks it as truncated
+pub(crate) fn truncate(text: &str, max_length: usize) -> String {
+    if char_count(text) <= max_length {
+        return text.to_string();
+    }
+    let mut truncated = text
+        .chars()
+        .take(max_length.saturating_sub(1))
+        .collect::<String>();
+    truncated.push('…');
+
+    truncated
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_valid_chunks(chunks: &[String], max_length: usize) {
+        for chunk in chunks {
+            assert!(
+                char_count(chunk) <= max_length,
+                "chunk has {} characters: {:?}",
+                char_count(chunk),
+                chunk
+            );
+            assert_eq!(chunk.matches(CODE_FENCE).count() % 2, 0, "{:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn empty_text_produces_one_chunk() {
+        assert_eq!(split_text("", MAX_CONTENT_LENGTH), vec![String::new()]);
+        assert_eq!(
+            split_text("\n\n \n", MAX_CONTENT_LENGTH),
+            vec![String::new()]
+        );
+    }
+
+    #[test]
+    fn short_text_is_kept() {
+        let text = "first line\nsecond line";
+
+        assert_eq!(split_text(text, MAX_CONTENT_LENGTH), vec![text.to_string()]);
+    }
+
+    #[test]
+    fn leading_blank_lines_do_not_exceed_the_limit() {
+        let text = format!("{}{}", "\n".repeat(10), "a".repeat(1999));
+        let chunks = split_text(&text, MAX_CONTENT_LENGTH);
+
+        assert_valid_chunks(&chunks, MAX_CONTENT_LENGTH);
+        assert_eq!(chunks, vec!["a".repeat(1999)]);
+    }
+
+    #[test]
+    fn splits_at_line_breaks() {
+        let text = "aaaa\nbbbb\ncccc";
+
+        assert_eq!(split_text(text, 10), vec!["aaaa\nbbbb", "cccc"]);
+    }
+
+    #[test]
+    fn splits_long_lines_at_spaces_and_long_words() {
+        let text = format!("aaa bbb ccc {}", "d".repeat(25));
+        let chunks = split_text(&text, 10);
+
+        assert_valid_chunks(&chunks, 10);
+        assert_eq!(chunks[0], "aaa bbb");
+        assert_eq!(chunks.concat().replace(' ', ""), text.replace(' ', ""));
+    }
+
+    #[test]
+    fn code_fences_are_reopened_with_their_language() {
+        let lines = (0..10).map(|i| format!("line {}", i)).collect::<Vec<_>>();
+        let text = format!("```rust\n{}\n```\nafter", lines.join("\n"));
+        let chunks = split_text(&text, 40);
+
+        assert_valid_chunks(&chunks, 40);
+        assert!(chunks.len() > 1);
+        assert!(chunks[1].starts_with("```rust\n"));
+    }
+
+    #[test]
+    fn closing_fences_on_page_boundaries_do_not_create_empty_blocks() {
+        for length in 1..60 {
+            let text = format!("```\n{}\n```\nafter", "a".repeat(length));
+            let chunks = split_text(&text, 20);
+
+            assert_valid_chunks(&chunks, 20);
+            for chunk in &chunks {
+                assert!(!chunk.contains("```\n```"), "{:?}", chunks);
+            }
+            assert!(
+                chunks.last().unwrap().ends_with("\nafter") || chunks.last().unwrap() == "after"
+            );
+        }
+    }
+
+    #[test]
+    fn chunks_never_exceed_the_limit() {
+        let text = (0..200)
+            .map(|i| match i % 7 {
+                0 => "```sh".to_string(),
+                3 => "```".to_string(),
+                5 => String::new(),
+                _ => "word ".repeat(i % 13),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        for max_length in [30, 64, 100, MAX_CONTENT_LENGTH] {
+            assert_valid_chunks(&split_text(&text, max_length), max_length);
+        }
+    }
+}
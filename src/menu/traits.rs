@@ -1,6 +1,7 @@
 use crate::error::Result;
 use serenity::client::Context;
 use serenity::http::Http;
+use serenity::model::interactions::message_component::MessageComponentInteraction;
 use serenity::{async_trait, model::prelude::*};
 
 #[async_trait]
@@ -20,6 +21,11 @@ pub trait EventDrivenMessage: Send + Sync {
         Ok(())
     }
 
+    /// Fired when the bot is shutting down
+    async fn on_shutdown(&mut self, _ctx: &Context) -> Result<()> {
+        Ok(())
+    }
+
     /// Fired when a reaction was added to the message
     async fn on_reaction_add(&mut self, _ctx: &Context, _reaction: Reaction) -> Result<()> {
         Ok(())
@@ -29,4 +35,18 @@ pub trait EventDrivenMessage: Send + Sync {
     async fn on_reaction_remove(&mut self, _ctx: &Context, _reaction: Reaction) -> Result<()> {
         Ok(())
     }
+
+    /// Fired when a new message was created in the channel of the message
+    async fn on_message_create(&mut self, _ctx: &Context, _message: Message) -> Result<()> {
+        Ok(())
+    }
+
+    /// Fired when a component of the message was interacted with
+    async fn on_interaction_create(
+        &mut self,
+        _ctx: &Context,
+        _interaction: MessageComponentInteraction,
+    ) -> Result<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,665 @@
+use crate::core::MessageHandle;
+use crate::error::{Error, Result};
+use crate::menu::menu::{
+    control_key, parse_control_emoji, BUTTONS_PER_ROW, MAX_ACTION_ROWS, MAX_OPTION_LABEL_LENGTH,
+};
+use crate::menu::text::{truncate, MAX_EMBED_FIELD_VALUE_LENGTH, MAX_EMBED_TITLE_LENGTH};
+use crate::menu::traits::EventDrivenMessage;
+use crate::menu::{get_listeners_from_context, AccessPolicy, ControlEvent, ControlMode};
+use serenity::async_trait;
+use serenity::builder::{CreateComponents, CreateEmbed, CreateMessage};
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::channel::{Reaction, ReactionType};
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
+use serenity::utils::Colour;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{oneshot, Mutex};
+
+/// The emojis that are assigned to poll options without an explicit emoji
+pub static POLL_OPTION_EMOJIS: [&str; 10] =
+    ["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣", "9️⃣", "🔟"];
+/// Custom id prefix of the buttons of a poll
+pub static POLL_OPTION_ID: &str = "poll_option";
+
+/// The number of characters of a result bar
+const BAR_LENGTH: usize = 16;
+/// Discord allows up to 20 reactions per message
+const MAX_REACTIONS: usize = 20;
+/// The maximum number of characters of a button label
+const MAX_BUTTON_LABEL_LENGTH: usize = 80;
+/// The minimum time between two edits of the poll message after votes
+const POLL_EDIT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How many options a user can vote for
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum VoteMode {
+    /// A new vote replaces the previous vote of the user
+    #[default]
+    Single,
+    /// Users can vote for multiple options
+    Multiple,
+}
+
+/// The number of votes a poll option received
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptionResult {
+    pub label: String,
+    pub emoji: ReactionType,
+    pub votes: usize,
+}
+
+/// The results of a poll
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollResults {
+    pub question: String,
+    pub options: Vec<OptionResult>,
+    /// The number of users that voted
+    pub voters: usize,
+}
+
+impl PollResults {
+    /// Returns the options with the most votes
+    pub fn winners(&self) -> Vec<&OptionResult> {
+        let max_votes = self.options.iter().map(|o| o.votes).max().unwrap_or(0);
+
+        self.options
+            .iter()
+            .filter(|o| max_votes > 0 && o.votes == max_votes)
+            .collect()
+    }
+}
+
+struct PollOption {
+    label: String,
+    emoji: ReactionType,
+}
+
+/// A message that collects votes and displays the current results
+pub struct Poll {
+    message: MessageHandle,
+    question: String,
+    options: Vec<PollOption>,
+    votes: HashMap<UserId, HashSet<usize>>,
+    vote_mode: VoteMode,
+    control_mode: ControlMode,
+    access: AccessPolicy,
+    colour: Option<Colour>,
+    closes_at: SystemTime,
+    post_results: bool,
+    sender: Option<oneshot::Sender<PollResults>>,
+    closed: bool,
+    /// If votes changed since the message was edited
+    dirty: bool,
+    last_edit: Instant,
+    edit_scheduled: bool,
+}
+
+impl Poll {
+    /// Returns the current results of the poll
+    pub fn results(&self) -> PollResults {
+        let options = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, o)| OptionResult {
+                label: o.label.clone(),
+                emoji: o.emoji.clone(),
+                votes: self.votes.values().filter(|v| v.contains(&i)).count(),
+            })
+            .collect();
+
+        PollResults {
+            question: self.question.clone(),
+            options,
+            voters: self.votes.values().filter(|v| !v.is_empty()).count(),
+        }
+    }
+
+    /// Adds the vote of the user for the given option.
+    /// Returns the options that were voted for previously and are replaced
+    fn add_vote(&mut self, user_id: UserId, option: usize) -> Vec<usize> {
+        let votes = self.votes.entry(user_id).or_default();
+        let replaced = match self.vote_mode {
+            VoteMode::Single => mem::take(votes)
+                .into_iter()
+                .filter(|o| *o != option)
+                .collect(),
+            VoteMode::Multiple => Vec::new(),
+        };
+        votes.insert(option);
+
+        replaced
+    }
+
+    /// Removes the vote of the user for the given option
+    fn remove_vote(&mut self, user_id: UserId, option: usize) -> bool {
+        self.votes
+            .get_mut(&user_id)
+            .is_some_and(|votes| votes.remove(&option))
+    }
+
+    fn create_embed(&self, embed: &mut CreateEmbed) {
+        let results = self.results();
+        let total_votes = results.options.iter().map(|o| o.votes).sum::<usize>();
+        let description = results
+            .options
+            .iter()
+            .map(|o| {
+                let share = if total_votes > 0 {
+                    o.votes as f64 / total_votes as f64
+                } else {
+                    0.0
+                };
+                let filled = (share * BAR_LENGTH as f64).round() as usize;
+                format!(
+                    "{} **{}**\n`{}{}` {:.0}% ({})",
+                    o.emoji,
+                    truncate(&o.label, MAX_OPTION_LABEL_LENGTH),
+                    "█".repeat(filled),
+                    "░".repeat(BAR_LENGTH - filled),
+                    share * 100.0,
+                    o.votes
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let closes_at = self
+            .closes_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let status = if self.closed {
+            format!("Closed <t:{}:R>", closes_at)
+        } else {
+            format!("Closes <t:{}:R>", closes_at)
+        };
+
+        embed
+            .title(truncate(&self.question, MAX_EMBED_TITLE_LENGTH))
+            .description(format!("{}\n\n{}", description, status))
+            .footer(|f| f.text(format!("{} voters", results.voters)));
+        if let Some(colour) = self.colour {
+            embed.colour(colour);
+        }
+    }
+
+    fn create_components(&self) -> CreateComponents {
+        let mut components = CreateComponents::default();
+        if self.control_mode != ControlMode::Buttons || self.closed {
+            return components;
+        }
+        let results = self.results();
+
+        for (row_index, row) in results
+            .options
+            .chunks(BUTTONS_PER_ROW)
+            .enumerate()
+            .take(MAX_ACTION_ROWS)
+        {
+            components.create_action_row(|r| {
+                for (i, option) in row.iter().enumerate() {
+                    let index = row_index * BUTTONS_PER_ROW + i;
+                    let votes = format!(" ({})", option.votes);
+                    let label = truncate(
+                        &option.label,
+                        MAX_BUTTON_LABEL_LENGTH - votes.chars().count(),
+                    );
+                    r.create_button(|b| {
+                        b.style(ButtonStyle::Secondary)
+                            .custom_id(format!("{}:{}", POLL_OPTION_ID, index))
+                            .emoji(option.emoji.clone())
+                            .label(format!("{}{}", label, votes))
+                    });
+                }
+                r
+            });
+        }
+
+        components
+    }
+
+    /// Creates the message of the poll with its current state
+    fn render(&self) -> CreateMessage<'static> {
+        let mut message = CreateMessage::default();
+        message.embed(|e| {
+            self.create_embed(e);
+            e
+        });
+        message.set_components(self.create_components());
+
+        message
+    }
+
+    /// Displays the current state of the poll
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn display(&mut self, http: &Http) -> Result<()> {
+        self.dirty = false;
+        self.edit_scheduled = false;
+        self.last_edit = Instant::now();
+        http.edit_message(
+            self.message.channel_id,
+            self.message.message_id,
+            &serde_json::to_value(self.render().0).unwrap(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Closes the poll, displays the final results
+    /// and resolves the handle of the poll
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn close(&mut self, http: &Http) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        self.closes_at = SystemTime::now();
+        let results = self.results();
+
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(results.clone());
+        }
+        self.display(http).await?;
+
+        if self.control_mode == ControlMode::Reactions {
+            http.delete_message_reactions(self.message.channel_id, self.message.message_id)
+                .await?;
+        }
+        if self.post_results {
+            let winners = results
+                .winners()
+                .into_iter()
+                .map(|o| format!("{} **{}**", o.emoji, o.label))
+                .collect::<Vec<String>>();
+            let summary = if winners.is_empty() {
+                "Nobody voted".to_string()
+            } else {
+                let winners = format!("Winner: {}", winners.join(", "));
+                truncate(&winners, MAX_EMBED_FIELD_VALUE_LENGTH)
+            };
+            let title = truncate(
+                &format!("Results: {}", self.question),
+                MAX_EMBED_TITLE_LENGTH,
+            );
+            let mut message = CreateMessage::default();
+            message.embed(|e| {
+                self.create_embed(e);
+                e.title(title).field("Result", summary, false)
+            });
+            http.send_message(
+                self.message.channel_id,
+                &serde_json::to_value(message.0).unwrap(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Displays the changed votes at most once per interval.
+    /// Changes within the interval are displayed by a delayed update
+    async fn display_votes(&mut self, ctx: &Context) -> Result<()> {
+        self.dirty = true;
+        let elapsed = self.last_edit.elapsed();
+
+        if elapsed >= POLL_EDIT_INTERVAL {
+            return self.display(&ctx.http).await;
+        }
+        if self.edit_scheduled {
+            return Ok(());
+        }
+        let listeners = get_listeners_from_context(ctx).await?;
+        let poll = listeners.get(&self.message).map(|e| e.value().clone());
+
+        if let Some(poll) = poll {
+            self.edit_scheduled = true;
+            let http = Arc::clone(&ctx.http);
+
+            tokio::spawn(async move {
+                tokio::time::sleep(POLL_EDIT_INTERVAL - elapsed).await;
+                let mut poll = poll.lock().await;
+
+                if let Err(e) = poll.update(&http).await {
+                    tracing::error!("Failed to update poll: {:?}", e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the handle of the poll without displaying the results
+    fn abort(&mut self) {
+        self.closed = true;
+
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(self.results());
+        }
+    }
+}
+
+#[async_trait]
+impl EventDrivenMessage for Poll {
+    fn is_frozen(&self) -> bool {
+        self.closed
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        if !self.closed && SystemTime::now() >= self.closes_at {
+            tracing::debug!("Poll deadline reached");
+            self.close(http).await?;
+        } else if !self.closed && self.dirty {
+            self.display(http).await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, _ctx: &Context) -> Result<()> {
+        self.abort();
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_shutdown(&mut self, _ctx: &Context) -> Result<()> {
+        self.abort();
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_reaction_add(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        if self.closed || self.control_mode != ControlMode::Reactions {
+            return Ok(());
+        }
+        let current_user = ctx.http.get_current_user().await?;
+        let user_id = reaction.user_id.ok_or(Error::NoCache)?;
+
+        if user_id == current_user.id {
+            return Ok(());
+        }
+        let key = control_key(&reaction.emoji);
+        let option = match self
+            .options
+            .iter()
+            .position(|o| control_key(&o.emoji) == key)
+        {
+            Some(option) => option,
+            None => return Ok(()),
+        };
+        let event = ControlEvent::Reaction(Box::new(reaction.clone()));
+
+        if !self.access.is_permitted(ctx, &event).await? {
+            tracing::debug!("User is not permitted to vote");
+            reaction.delete(ctx).await?;
+            return Ok(());
+        }
+        let replaced = self.add_vote(user_id, option);
+        self.display_votes(ctx).await?;
+
+        for option in replaced {
+            ctx.http
+                .delete_reaction(
+                    self.message.channel_id,
+                    self.message.message_id,
+                    Some(user_id.0),
+                    &self.options[option].emoji,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_reaction_remove(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        if self.closed || self.control_mode != ControlMode::Reactions {
+            return Ok(());
+        }
+        let user_id = reaction.user_id.ok_or(Error::NoCache)?;
+        let key = control_key(&reaction.emoji);
+
+        if let Some(option) = self
+            .options
+            .iter()
+            .position(|o| control_key(&o.emoji) == key)
+        {
+            if self.remove_vote(user_id, option) {
+                self.display_votes(ctx).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_interaction_create(
+        &mut self,
+        ctx: &Context,
+        interaction: MessageComponentInteraction,
+    ) -> Result<()> {
+        interaction.defer(&ctx.http).await?;
+
+        if self.closed {
+            return Ok(());
+        }
+        let option = interaction
+            .data
+            .custom_id
+            .strip_prefix(POLL_OPTION_ID)
+            .and_then(|id| id.strip_prefix(':'))
+            .and_then(|id| id.parse::<usize>().ok())
+            .filter(|o| *o < self.options.len());
+        let option = match option {
+            Some(option) => option,
+            None => return Ok(()),
+        };
+        let user_id = interaction.user.id;
+        let event = ControlEvent::Component(Box::new(interaction));
+
+        if !self.access.is_permitted(ctx, &event).await? {
+            tracing::debug!("User is not permitted to vote");
+            return Ok(());
+        }
+        // pressing the button of an option that was voted for removes the vote
+        if !self.remove_vote(user_id, option) {
+            self.add_vote(user_id, option);
+        }
+
+        self.display_votes(ctx).await
+    }
+}
+
+/// A handle to a running poll
+pub struct PollHandle {
+    message: MessageHandle,
+    receiver: oneshot::Receiver<PollResults>,
+}
+
+impl PollHandle {
+    /// Returns the handle of the poll message
+    pub fn message(&self) -> MessageHandle {
+        self.message
+    }
+
+    /// Waits for the poll to close and returns the final results.
+    /// Returns `None` if the poll stopped without results
+    pub async fn wait(self) -> Option<PollResults> {
+        self.receiver.await.ok()
+    }
+}
+
+/// A builder for polls
+pub struct PollBuilder {
+    question: String,
+    options: Vec<(Option<ReactionType>, String)>,
+    vote_mode: VoteMode,
+    control_mode: ControlMode,
+    access: AccessPolicy,
+    colour: Option<Colour>,
+    duration: Duration,
+    post_results: bool,
+}
+
+impl PollBuilder {
+    /// Creates a new poll builder with the given question
+    pub fn new<S: ToString>(question: S) -> Self {
+        Self {
+            question: question.to_string(),
+            options: Vec::new(),
+            vote_mode: VoteMode::default(),
+            control_mode: ControlMode::default(),
+            access: AccessPolicy::default(),
+            colour: None,
+            duration: Duration::from_secs(3600),
+            post_results: true,
+        }
+    }
+
+    /// Adds an option with a number emoji
+    pub fn add_option<S: ToString>(mut self, label: S) -> Self {
+        self.options.push((None, label.to_string()));
+
+        self
+    }
+
+    /// Adds an option with the given emoji
+    pub fn add_option_with_emoji<S1: ToString, S2: ToString>(
+        mut self,
+        emoji: S1,
+        label: S2,
+    ) -> Self {
+        self.options
+            .push((Some(parse_control_emoji(emoji)), label.to_string()));
+
+        self
+    }
+
+    /// Sets how many options a user can vote for
+    pub fn vote_mode(mut self, mode: VoteMode) -> Self {
+        self.vote_mode = mode;
+
+        self
+    }
+
+    /// Sets if votes are given with reactions or buttons
+    pub fn control_mode(mut self, mode: ControlMode) -> Self {
+        self.control_mode = mode;
+
+        self
+    }
+
+    /// Sets the policy that decides who can vote
+    pub fn access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access = policy;
+
+        self
+    }
+
+    /// Sets the colour of the poll embed
+    pub fn colour<C: Into<Colour>>(mut self, colour: C) -> Self {
+        self.colour = Some(colour.into());
+
+        self
+    }
+
+    /// Sets how long the poll accepts votes
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+
+        self
+    }
+
+    /// Sets if a message with the final results is posted when the poll closes
+    pub fn post_results(mut self, value: bool) -> Self {
+        self.post_results = value;
+
+        self
+    }
+
+    /// Creates the poll in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build(self, ctx: &Context, channel_id: ChannelId) -> Result<PollHandle> {
+        let max_options = match self.control_mode {
+            ControlMode::Reactions => MAX_REACTIONS,
+            ControlMode::Buttons => BUTTONS_PER_ROW * MAX_ACTION_ROWS,
+        };
+        if self.options.is_empty() || self.options.len() > max_options {
+            return Err(Error::Msg(format!(
+                "A poll needs between 1 and {} options",
+                max_options
+            )));
+        }
+        let mut number_emojis = POLL_OPTION_EMOJIS.iter();
+        #[allow(clippy::result_large_err)]
+        let options = self
+            .options
+            .into_iter()
+            .map(|(emoji, label)| {
+                let emoji = emoji
+                    .or_else(|| number_emojis.next().map(parse_control_emoji))
+                    .ok_or_else(|| {
+                        Error::Msg("Not enough number emojis for poll options".to_string())
+                    })?;
+                Ok(PollOption { label, emoji })
+            })
+            .collect::<Result<Vec<PollOption>>>()?;
+        let (sender, receiver) = oneshot::channel();
+
+        let mut poll = Poll {
+            message: MessageHandle::from_raw_ids(channel_id.0, 0),
+            question: self.question,
+            options,
+            votes: HashMap::new(),
+            vote_mode: self.vote_mode,
+            control_mode: self.control_mode,
+            access: self.access,
+            colour: self.colour,
+            closes_at: SystemTime::now() + self.duration,
+            post_results: self.post_results,
+            sender: Some(sender),
+            closed: false,
+            dirty: false,
+            last_edit: Instant::now(),
+            edit_scheduled: false,
+        };
+        let mut page = poll.render();
+        let message = channel_id
+            .send_message(ctx, |msg| {
+                mem::swap(msg, &mut page);
+                msg
+            })
+            .await?;
+        poll.message = MessageHandle::new(message.channel_id, message.id);
+        let handle = poll.message;
+        let reactions = poll
+            .options
+            .iter()
+            .map(|o| o.emoji.clone())
+            .collect::<Vec<ReactionType>>();
+        let control_mode = poll.control_mode;
+
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.insert(handle, Arc::new(Mutex::new(Box::new(poll).into())));
+
+        if control_mode == ControlMode::Reactions {
+            for emoji in reactions {
+                message.react(ctx, emoji).await?;
+            }
+        }
+
+        Ok(PollHandle {
+            message: handle,
+            receiver,
+        })
+    }
+}
@@ -0,0 +1,309 @@
+use crate::core::MessageHandle;
+use crate::error::Result;
+use crate::menu::get_listeners_from_context;
+use crate::menu::traits::EventDrivenMessage;
+use serenity::async_trait;
+use serenity::builder::CreateMessage;
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+use serenity::utils::Colour;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+
+/// The default minimum time between two edits of a progress message
+pub static DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The number of characters of the progress bar
+const BAR_LENGTH: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+enum ProgressStatus {
+    Running,
+    Finished(String),
+    Failed(String),
+}
+
+struct ProgressState {
+    progress: f64,
+    text: String,
+    status: ProgressStatus,
+    dirty: bool,
+    flush_pending: bool,
+    last_edit: Instant,
+    cancelled: bool,
+}
+
+/// The state shared between a progress message and its handles
+struct ProgressShared {
+    message: MessageHandle,
+    title: Option<String>,
+    min_interval: Duration,
+    state: std::sync::Mutex<ProgressState>,
+    /// Serializes edits so that the last edit always shows the latest state
+    edit_lock: Mutex<()>,
+}
+
+impl ProgressShared {
+    fn render(&self, state: &ProgressState) -> CreateMessage<'static> {
+        let (progress, colour) = match &state.status {
+            ProgressStatus::Running => (state.progress, None),
+            ProgressStatus::Finished(_) => (1.0, Some(Colour::DARK_GREEN)),
+            ProgressStatus::Failed(_) => (state.progress, Some(Colour::RED)),
+        };
+        let filled = (progress * BAR_LENGTH as f64).round() as usize;
+        let bar = format!(
+            "`{}{}` {:.0}%",
+            "█".repeat(filled),
+            "░".repeat(BAR_LENGTH - filled),
+            progress * 100.0
+        );
+        let text = match &state.status {
+            ProgressStatus::Running => state.text.clone(),
+            ProgressStatus::Finished(result) => format!("✅ {}", result),
+            ProgressStatus::Failed(error) => format!("❌ {}", error),
+        };
+
+        let mut message = CreateMessage::default();
+        message.embed(|e| {
+            if let Some(title) = &self.title {
+                e.title(title);
+            }
+            if let Some(colour) = colour {
+                e.colour(colour);
+            }
+            e.description(format!("{}\n{}", bar, text))
+        });
+
+        message
+    }
+
+    /// Displays the latest state if it changed since the last edit
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn flush(&self, http: &Http) -> Result<()> {
+        let _guard = self.edit_lock.lock().await;
+        let message = {
+            let mut state = self.state.lock().unwrap();
+            state.flush_pending = false;
+
+            if !state.dirty || state.cancelled {
+                return Ok(());
+            }
+            state.dirty = false;
+            state.last_edit = Instant::now();
+            self.render(&state)
+        };
+        http.edit_message(
+            self.message.channel_id,
+            self.message.message_id,
+            &serde_json::to_value(message.0).unwrap(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// A cloneable handle to update a progress message from background tasks.
+/// Updates are throttled and stop once the message was deleted
+#[derive(Clone)]
+pub struct ProgressHandle {
+    http: Arc<Http>,
+    runtime: Handle,
+    shared: Arc<ProgressShared>,
+}
+
+impl ProgressHandle {
+    /// Returns the handle of the progress message
+    pub fn message(&self) -> MessageHandle {
+        self.shared.message
+    }
+
+    /// Returns if the message was deleted and updates are no longer displayed
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.state.lock().unwrap().cancelled
+    }
+
+    /// Sets the progress between `0.0` and `1.0` and the status text.
+    /// Values that are not finite are treated as `0.0`.
+    /// The message is edited at most once per interval,
+    /// updates within the interval are displayed when it has passed.
+    /// Can be called from threads outside of the tokio runtime
+    pub fn set<S: ToString>(&self, progress: f64, status: S) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.cancelled || state.status != ProgressStatus::Running {
+            return;
+        }
+        state.progress = if progress.is_finite() {
+            progress.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        state.text = status.to_string();
+        state.dirty = true;
+
+        if !state.flush_pending {
+            state.flush_pending = true;
+            let delay = self
+                .shared
+                .min_interval
+                .saturating_sub(state.last_edit.elapsed());
+            let handle = self.clone();
+
+            self.runtime.spawn(async move {
+                tokio::time::sleep(delay).await;
+
+                if let Err(e) = handle.shared.flush(&handle.http).await {
+                    tracing::error!("Failed to update progress message: {:?}", e);
+                }
+            });
+        }
+    }
+
+    /// Marks the task as finished and displays the given result
+    pub async fn finish<S: ToString>(&self, result: S) -> Result<()> {
+        self.complete(ProgressStatus::Finished(result.to_string()))
+            .await
+    }
+
+    /// Marks the task as failed and displays the given error
+    pub async fn fail<S: ToString>(&self, error: S) -> Result<()> {
+        self.complete(ProgressStatus::Failed(error.to_string()))
+            .await
+    }
+
+    async fn complete(&self, status: ProgressStatus) -> Result<()> {
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            if state.cancelled || state.status != ProgressStatus::Running {
+                return Ok(());
+            }
+            state.status = status;
+            state.dirty = true;
+        }
+
+        self.shared.flush(&self.http).await
+    }
+}
+
+/// A message that displays the progress of a long running task
+pub struct ProgressMessage {
+    shared: Arc<ProgressShared>,
+}
+
+#[async_trait]
+impl EventDrivenMessage for ProgressMessage {
+    fn is_frozen(&self) -> bool {
+        let state = self.shared.state.lock().unwrap();
+
+        state.cancelled || (state.status != ProgressStatus::Running && !state.dirty)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        self.shared.flush(http).await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, _ctx: &Context) -> Result<()> {
+        tracing::debug!("Progress message deleted. Cancelling updates");
+        self.shared.state.lock().unwrap().cancelled = true;
+
+        Ok(())
+    }
+}
+
+/// A builder for progress messages
+pub struct ProgressBuilder {
+    title: Option<String>,
+    status: String,
+    min_interval: Duration,
+}
+
+impl Default for ProgressBuilder {
+    fn default() -> Self {
+        Self {
+            title: None,
+            status: String::new(),
+            min_interval: DEFAULT_PROGRESS_INTERVAL,
+        }
+    }
+}
+
+impl ProgressBuilder {
+    /// Creates a new progress message builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title of the progress embed
+    pub fn title<S: ToString>(mut self, title: S) -> Self {
+        self.title = Some(title.to_string());
+
+        self
+    }
+
+    /// Sets the initial status text
+    pub fn status<S: ToString>(mut self, status: S) -> Self {
+        self.status = status.to_string();
+
+        self
+    }
+
+    /// Sets the minimum time between two edits of the message
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+
+        self
+    }
+
+    /// Creates the progress message in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build(self, ctx: &Context, channel_id: ChannelId) -> Result<ProgressHandle> {
+        let mut shared = ProgressShared {
+            message: MessageHandle::from_raw_ids(channel_id.0, 0),
+            title: self.title,
+            min_interval: self.min_interval,
+            state: std::sync::Mutex::new(ProgressState {
+                progress: 0.0,
+                text: self.status,
+                status: ProgressStatus::Running,
+                dirty: false,
+                flush_pending: false,
+                last_edit: Instant::now(),
+                cancelled: false,
+            }),
+            edit_lock: Mutex::new(()),
+        };
+        let mut page = shared.render(&shared.state.lock().unwrap());
+
+        let message = channel_id
+            .send_message(ctx, |msg| {
+                mem::swap(msg, &mut page);
+                msg
+            })
+            .await?;
+        shared.message = MessageHandle::new(message.channel_id, message.id);
+        let shared = Arc::new(shared);
+
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.insert(
+            shared.message,
+            Arc::new(Mutex::new(
+                Box::new(ProgressMessage {
+                    shared: Arc::clone(&shared),
+                })
+                .into(),
+            )),
+        );
+
+        Ok(ProgressHandle {
+            http: Arc::clone(&ctx.http),
+            runtime: Handle::current(),
+            shared,
+        })
+    }
+}
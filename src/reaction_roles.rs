@@ -0,0 +1,196 @@
+use crate::core::MessageHandle;
+use crate::error::{Error, Result};
+use crate::menu::get_listeners_from_context;
+use crate::menu::menu::{control_key, parse_control_emoji};
+use crate::menu::traits::EventDrivenMessage;
+use serenity::async_trait;
+use serenity::builder::CreateMessage;
+use serenity::client::Context;
+use serenity::model::channel::{Message, Reaction, ReactionType};
+use serenity::model::id::{ChannelId, RoleId};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+static AUDIT_LOG_REASON: &str = "Reaction role";
+
+/// Decides how roles are granted and revoked
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RoleMode {
+    /// Adding a reaction grants the role and removing it revokes the role
+    #[default]
+    Toggle,
+    /// Users can only have one role of the message.
+    /// Granting a role revokes the other roles of the message
+    Unique,
+    /// Roles are granted but never revoked
+    Verify,
+    /// Users can have at most the given number of roles of the message
+    Limit(usize),
+}
+
+/// A message that grants roles to users that react with the emoji of the role.
+/// The message never expires and doesn't remove reactions
+pub struct ReactionRoles {
+    roles: Vec<(ReactionType, RoleId)>,
+    mode: RoleMode,
+}
+
+impl ReactionRoles {
+    /// Returns the role that belongs to the given emoji
+    fn get_role(&self, emoji: &ReactionType) -> Option<RoleId> {
+        let key = control_key(emoji);
+
+        self.roles
+            .iter()
+            .find(|(e, _)| control_key(e) == key)
+            .map(|(_, r)| *r)
+    }
+}
+
+#[async_trait]
+impl EventDrivenMessage for ReactionRoles {
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_reaction_add(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        let (role_id, guild_id, user_id) = match (
+            self.get_role(&reaction.emoji),
+            reaction.guild_id,
+            reaction.user_id,
+        ) {
+            (Some(role), Some(guild), Some(user)) => (role, guild, user),
+            _ => return Ok(()),
+        };
+        let current_user = ctx.http.get_current_user().await?;
+
+        if user_id == current_user.id {
+            return Ok(());
+        }
+        let member = guild_id.member(ctx, user_id).await?;
+
+        if member.roles.contains(&role_id) {
+            return Ok(());
+        }
+        let owned_roles = self
+            .roles
+            .iter()
+            .map(|(_, r)| *r)
+            .filter(|r| member.roles.contains(r))
+            .collect::<Vec<RoleId>>();
+
+        match self.mode {
+            RoleMode::Limit(max) if owned_roles.len() >= max => {
+                tracing::debug!("User already has the maximum number of roles");
+                return Ok(());
+            }
+            RoleMode::Unique => {
+                for role in owned_roles {
+                    tracing::debug!("Revoking role {}", role);
+                    ctx.http
+                        .remove_member_role(guild_id.0, user_id.0, role.0, Some(AUDIT_LOG_REASON))
+                        .await?;
+                }
+            }
+            _ => {}
+        }
+        tracing::debug!("Granting role {}", role_id);
+        ctx.http
+            .add_member_role(guild_id.0, user_id.0, role_id.0, Some(AUDIT_LOG_REASON))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_reaction_remove(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        if self.mode == RoleMode::Verify {
+            return Ok(());
+        }
+        if let (Some(role_id), Some(guild_id), Some(user_id)) = (
+            self.get_role(&reaction.emoji),
+            reaction.guild_id,
+            reaction.user_id,
+        ) {
+            tracing::debug!("Revoking role {}", role_id);
+            ctx.http
+                .remove_member_role(guild_id.0, user_id.0, role_id.0, Some(AUDIT_LOG_REASON))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder for reaction role messages
+#[derive(Default)]
+pub struct ReactionRolesBuilder {
+    roles: Vec<(ReactionType, RoleId)>,
+    mode: RoleMode,
+}
+
+impl ReactionRolesBuilder {
+    /// Creates a new reaction role builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grants the given role to users reacting with the given emoji
+    pub fn add_role<S: ToString>(mut self, emoji: S, role_id: RoleId) -> Self {
+        self.roles.push((parse_control_emoji(emoji), role_id));
+
+        self
+    }
+
+    /// Sets how roles are granted and revoked
+    pub fn mode(mut self, mode: RoleMode) -> Self {
+        self.mode = mode;
+
+        self
+    }
+
+    /// Creates a new reaction role message in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build<'a, F>(
+        self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        f: F,
+    ) -> Result<MessageHandle>
+    where
+        F: for<'b> FnOnce(&'b mut CreateMessage<'a>) -> &'b mut CreateMessage<'a>,
+    {
+        let message = channel_id.send_message(ctx, f).await?;
+
+        self.attach(ctx, &message).await
+    }
+
+    /// Handles reaction roles on an existing message.
+    /// Can be used to restore reaction role messages after a restart
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn attach(self, ctx: &Context, message: &Message) -> Result<MessageHandle> {
+        if self.roles.is_empty() {
+            return Err(Error::Msg(
+                "A reaction role message needs at least one role".to_string(),
+            ));
+        }
+        let handle = MessageHandle::new(message.channel_id, message.id);
+        let emojis = self
+            .roles
+            .iter()
+            .map(|(e, _)| e.clone())
+            .collect::<Vec<ReactionType>>();
+        let reaction_roles = ReactionRoles {
+            roles: self.roles,
+            mode: self.mode,
+        };
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.insert(
+            handle,
+            Arc::new(Mutex::new(Box::new(reaction_roles).into())),
+        );
+
+        for emoji in emojis {
+            message.react(ctx, emoji).await?;
+        }
+
+        Ok(handle)
+    }
+}
@@ -0,0 +1,273 @@
+use crate::core::MessageHandle;
+use crate::error::Result;
+use crate::menu::traits::EventDrivenMessage;
+use crate::menu::{get_listeners_from_context, EventDrivenMessagesRef, MessageRef};
+use serde_json::Value;
+use serenity::async_trait;
+use serenity::builder::CreateMessage;
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// The default time without new messages before a sticky message is reposted
+pub static DEFAULT_STICKY_DEBOUNCE: Duration = Duration::from_secs(2);
+
+struct DebounceTimer {
+    repost_at: Option<Instant>,
+    running: bool,
+}
+
+/// Delays reposting a sticky message until no new messages
+/// were created in the channel for the debounce window
+pub(crate) struct StickyDebounce {
+    delay: Duration,
+    timer: Arc<std::sync::Mutex<DebounceTimer>>,
+    created_messages: Vec<MessageHandle>,
+}
+
+impl StickyDebounce {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            timer: Arc::new(std::sync::Mutex::new(DebounceTimer {
+                repost_at: None,
+                running: false,
+            })),
+            created_messages: Vec::new(),
+        }
+    }
+
+    /// Registers a message that was created after the sticky message
+    /// and restarts the debounce window.
+    /// The sticky message is updated once the window has passed
+    pub fn trigger(&mut self, http: Arc<Http>, sticky: Option<MessageRef>, created: MessageHandle) {
+        self.created_messages.push(created);
+        let mut timer = self.timer.lock().unwrap();
+        timer.repost_at = Some(Instant::now() + self.delay);
+
+        let sticky = match sticky {
+            Some(sticky) if !timer.running => sticky,
+            _ => return,
+        };
+        timer.running = true;
+        let timer = Arc::clone(&self.timer);
+
+        tokio::spawn(async move {
+            loop {
+                let repost_at = {
+                    let mut timer = timer.lock().unwrap();
+                    match timer.repost_at {
+                        Some(repost_at) if repost_at > Instant::now() => repost_at,
+                        _ => {
+                            timer.running = false;
+                            break;
+                        }
+                    }
+                };
+                tokio::time::sleep_until(repost_at.into()).await;
+            }
+            let mut sticky = sticky.lock().await;
+
+            if let Err(e) = sticky.update(&http).await {
+                tracing::error!("Failed to repost sticky message: {:?}", e);
+            }
+        });
+    }
+
+    /// Returns if the debounce window has passed and one of the created messages
+    /// is not a sticky message or menu itself. Resets the window
+    pub fn take_due(&mut self, listeners: &EventDrivenMessagesRef) -> bool {
+        let mut timer = self.timer.lock().unwrap();
+
+        match timer.repost_at {
+            Some(repost_at) if Instant::now() >= repost_at => {
+                timer.repost_at = None;
+                mem::take(&mut self.created_messages)
+                    .iter()
+                    .any(|handle| !listeners.contains_key(handle))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A message that is reposted whenever new messages were created in its channel
+/// so that it always stays the last one.
+/// Other sticky messages and menus don't cause a repost
+pub struct StickyMessage {
+    message: Arc<RwLock<MessageHandle>>,
+    content: Value,
+    debounce: StickyDebounce,
+    closed: Arc<AtomicBool>,
+    listeners: EventDrivenMessagesRef,
+}
+
+impl StickyMessage {
+    /// Sends the message again and deletes the previous one
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn repost(&self, http: &Http) -> Result<()> {
+        let old_handle = *self.message.read().await;
+        let message = http
+            .send_message(old_handle.channel_id, &self.content)
+            .await?;
+        let new_handle = {
+            let mut handle = self.message.write().await;
+            handle.message_id = message.id.0;
+            *handle
+        };
+        if let Some((_, sticky)) = self.listeners.remove(&old_handle) {
+            self.listeners.insert(new_handle, sticky);
+        }
+        http.delete_message(old_handle.channel_id, old_handle.message_id)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventDrivenMessage for StickyMessage {
+    fn is_frozen(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        if !self.is_frozen() && self.debounce.take_due(&self.listeners) {
+            tracing::debug!("Reposting sticky message");
+            self.repost(http).await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, _ctx: &Context) -> Result<()> {
+        tracing::debug!("Sticky message deleted");
+        self.closed.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn on_message_create(&mut self, ctx: &Context, message: Message) -> Result<()> {
+        if self.is_frozen() {
+            return Ok(());
+        }
+        let handle = *self.message.read().await;
+        let sticky = self.listeners.get(&handle).map(|e| e.value().clone());
+        let created = MessageHandle::new(message.channel_id, message.id);
+        self.debounce
+            .trigger(Arc::clone(&ctx.http), sticky, created);
+
+        Ok(())
+    }
+}
+
+/// A handle to a sticky message.
+/// The message handle changes every time the message is reposted
+#[derive(Clone)]
+pub struct StickyMessageHandle {
+    message: Arc<RwLock<MessageHandle>>,
+    closed: Arc<AtomicBool>,
+    listeners: EventDrivenMessagesRef,
+}
+
+impl StickyMessageHandle {
+    /// Returns the handle of the current message
+    pub async fn message(&self) -> MessageHandle {
+        *self.message.read().await
+    }
+
+    /// Stops reposting the message and keeps the current one
+    pub async fn unstick(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.listeners.remove(&*self.message.read().await);
+    }
+
+    /// Stops reposting the message and deletes the current one
+    pub async fn delete(&self, http: &Http) -> Result<()> {
+        self.closed.store(true, Ordering::Relaxed);
+        let handle = *self.message.read().await;
+        self.listeners.remove(&handle);
+        http.delete_message(handle.channel_id, handle.message_id)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// A builder for sticky messages
+pub struct StickyMessageBuilder {
+    debounce: Duration,
+}
+
+impl Default for StickyMessageBuilder {
+    fn default() -> Self {
+        Self {
+            debounce: DEFAULT_STICKY_DEBOUNCE,
+        }
+    }
+}
+
+impl StickyMessageBuilder {
+    /// Creates a new sticky message builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time without new messages in the channel before the message is reposted.
+    /// A burst of messages only causes a single repost
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+
+        self
+    }
+
+    /// Creates a new sticky message in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build<'a, F>(
+        self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        f: F,
+    ) -> Result<StickyMessageHandle>
+    where
+        F: for<'b> FnOnce(&'b mut CreateMessage<'a>) -> &'b mut CreateMessage<'a>,
+    {
+        let mut create_message = CreateMessage::default();
+        f(&mut create_message);
+        let content = serde_json::to_value(create_message.0).unwrap();
+        let message = ctx.http.send_message(channel_id.0, &content).await?;
+
+        let handle = Arc::new(RwLock::new(MessageHandle::new(
+            message.channel_id,
+            message.id,
+        )));
+        let closed = Arc::new(AtomicBool::new(false));
+        let listeners = get_listeners_from_context(ctx).await?;
+        let sticky = StickyMessage {
+            message: Arc::clone(&handle),
+            content,
+            debounce: StickyDebounce::new(self.debounce),
+            closed: Arc::clone(&closed),
+            listeners: Arc::clone(&listeners),
+        };
+        listeners.insert(
+            *handle.read().await,
+            Arc::new(Mutex::new(Box::new(sticky).into())),
+        );
+
+        Ok(StickyMessageHandle {
+            message: handle,
+            closed,
+            listeners,
+        })
+    }
+}
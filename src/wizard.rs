@@ -0,0 +1,554 @@
+use crate::core::{MessageHandle, MEDIUM_TIMEOUT};
+use crate::ephemeral_message::EphemeralMessage;
+use crate::error::{Error, Result};
+use crate::menu::menu::{control_key, parse_control_emoji, BUTTONS_PER_ROW, OPTIONS_PER_SELECT};
+use crate::menu::traits::EventDrivenMessage;
+use crate::menu::{get_listeners_from_context, Page};
+use futures::future::BoxFuture;
+use serde_json::json;
+use serenity::async_trait;
+use serenity::builder::CreateComponents;
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::channel::{Message, Reaction, ReactionType};
+use serenity::model::id::{ChannelId, UserId};
+use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Mutex};
+
+/// Custom id of the select menu of a wizard step
+pub static WIZARD_SELECT_ID: &str = "wizard_select";
+
+pub type StepHandlerArc<T> = Arc<
+    dyn for<'b> Fn(&'b Context, &'b mut T, WizardInput) -> BoxFuture<'b, Result<StepTransition>>
+        + Send
+        + Sync,
+>;
+
+/// The input a wizard step received
+#[derive(Clone, Debug)]
+pub enum WizardInput {
+    /// One of the reactions of the step was added
+    Reaction(ReactionType),
+    /// The button with the given custom id was pressed
+    Button(String),
+    /// The given values were selected in the select menu of the step
+    Select(Vec<String>),
+    /// A message was sent to the channel of the wizard
+    Message(Box<Message>),
+}
+
+impl WizardInput {
+    /// Returns the text of the input. This is the content of a message,
+    /// the id of a button, the first selected value or the emoji of a reaction
+    pub fn text(&self) -> Option<String> {
+        match self {
+            WizardInput::Reaction(emoji) => Some(emoji.to_string()),
+            WizardInput::Button(id) => Some(id.clone()),
+            WizardInput::Select(values) => values.first().cloned(),
+            WizardInput::Message(message) => Some(message.content.clone()),
+        }
+    }
+}
+
+/// What the wizard does after a step handled its input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepTransition {
+    /// Continues with the next step or finishes the wizard after the last step
+    Next,
+    /// Continues with the step with the given id
+    Goto(String),
+    /// Rejects the input, shows the given message and waits for new input
+    Retry(String),
+    /// Finishes the wizard successfully
+    Finish,
+    /// Cancels the wizard
+    Cancel,
+}
+
+/// The outcome of a wizard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WizardOutcome<T> {
+    /// All steps were completed. Contains the final state
+    Completed(T),
+    /// A step cancelled the wizard
+    Cancelled,
+    /// No input was received before the timeout was reached
+    TimedOut,
+    /// The wizard message was deleted
+    MessageDeleted,
+    /// The bot is shutting down
+    Shutdown,
+}
+
+/// A single step of a wizard that shows a prompt and collects input
+pub struct WizardStep<T> {
+    id: String,
+    prompt: Page<'static>,
+    reactions: Vec<ReactionType>,
+    buttons: Vec<(String, String, ButtonStyle)>,
+    select_options: Vec<(String, String)>,
+    accepts_text: bool,
+    handler: StepHandlerArc<T>,
+}
+
+impl<T> WizardStep<T> {
+    /// Creates a new step with the given id and prompt.
+    /// The handler validates the input and decides what happens next
+    pub fn new<S, F>(id: S, prompt: Page<'static>, handler: F) -> Self
+    where
+        S: ToString,
+        F: for<'b> Fn(&'b Context, &'b mut T, WizardInput) -> BoxFuture<'b, Result<StepTransition>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            id: id.to_string(),
+            prompt,
+            reactions: Vec::new(),
+            buttons: Vec::new(),
+            select_options: Vec::new(),
+            accepts_text: false,
+            handler: Arc::new(handler),
+        }
+    }
+
+    /// Accepts the given emoji as a reaction input
+    pub fn reaction<S: ToString>(mut self, emoji: S) -> Self {
+        self.reactions.push(parse_control_emoji(emoji));
+
+        self
+    }
+
+    /// Adds a button with the given custom id and label
+    pub fn button<S1: ToString, S2: ToString>(
+        mut self,
+        custom_id: S1,
+        label: S2,
+        style: ButtonStyle,
+    ) -> Self {
+        self.buttons
+            .push((custom_id.to_string(), label.to_string(), style));
+
+        self
+    }
+
+    /// Adds an option with the given value and label to the select menu of the step
+    pub fn select_option<S1: ToString, S2: ToString>(mut self, value: S1, label: S2) -> Self {
+        self.select_options
+            .push((value.to_string(), label.to_string()));
+
+        self
+    }
+
+    /// Accepts messages sent to the channel as input.
+    /// Requires the message and message content gateway intents
+    pub fn text_input(mut self) -> Self {
+        self.accepts_text = true;
+
+        self
+    }
+
+    fn create_components(&self) -> CreateComponents {
+        let mut components = CreateComponents::default();
+
+        for row in self.buttons.chunks(BUTTONS_PER_ROW) {
+            components.create_action_row(|r| {
+                for (id, label, style) in row {
+                    r.create_button(|b| b.style(*style).custom_id(id).label(label));
+                }
+                r
+            });
+        }
+        if !self.select_options.is_empty() {
+            if self.select_options.len() > OPTIONS_PER_SELECT {
+                tracing::warn!("Too many select options. Omitting the remaining options");
+            }
+            components.create_action_row(|r| {
+                r.create_select_menu(|s| {
+                    s.custom_id(WIZARD_SELECT_ID).options(|o| {
+                        for (value, label) in self.select_options.iter().take(OPTIONS_PER_SELECT) {
+                            o.create_option(|o| o.label(label).value(value));
+                        }
+                        o
+                    })
+                })
+            });
+        }
+
+        components
+    }
+}
+
+/// A message that guides a user through multiple steps
+/// and resolves to the collected state
+pub struct Wizard<T> {
+    message: MessageHandle,
+    steps: Vec<WizardStep<T>>,
+    current_step: usize,
+    state: Option<T>,
+    owner: Option<UserId>,
+    timeout: Duration,
+    expires_at: Instant,
+    delete_replies: bool,
+    completion_page: Option<Page<'static>>,
+    sender: Option<oneshot::Sender<WizardOutcome<T>>>,
+    finished: bool,
+}
+
+impl<T: Send + Sync + 'static> Wizard<T> {
+    /// Returns the id of the current step
+    pub fn current_step(&self) -> &str {
+        &self.steps[self.current_step].id
+    }
+
+    /// Shows the step with the given index
+    #[tracing::instrument(level = "debug", skip(self, http))]
+    async fn show_step(&mut self, http: &Http, index: usize) -> Result<()> {
+        let had_reactions = !self.steps[self.current_step].reactions.is_empty();
+        self.current_step = index;
+        let step = &self.steps[index];
+        let mut page = step.prompt.get().await?;
+        page.set_components(step.create_components());
+
+        http.edit_message(
+            self.message.channel_id,
+            self.message.message_id,
+            &serde_json::to_value(page.0).unwrap(),
+        )
+        .await?;
+        if had_reactions {
+            http.delete_message_reactions(self.message.channel_id, self.message.message_id)
+                .await?;
+        }
+        for emoji in &step.reactions {
+            http.create_reaction(self.message.channel_id, self.message.message_id, emoji)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Passes the input to the current step and runs the resulting transition
+    #[tracing::instrument(level = "debug", skip(self, ctx))]
+    async fn handle_input(&mut self, ctx: &Context, input: WizardInput) -> Result<()> {
+        let handler = Arc::clone(&self.steps[self.current_step].handler);
+        let state = self
+            .state
+            .as_mut()
+            .ok_or_else(|| Error::Msg("The wizard has already finished".to_string()))?;
+        let transition = handler(ctx, state, input).await?;
+        self.expires_at = Instant::now() + self.timeout;
+        tracing::debug!("Step transition is {:?}", transition);
+
+        match transition {
+            StepTransition::Next if self.current_step + 1 < self.steps.len() => {
+                self.show_step(&ctx.http, self.current_step + 1).await?
+            }
+            StepTransition::Next | StepTransition::Finish => {
+                let state = self
+                    .state
+                    .take()
+                    .ok_or_else(|| Error::Msg("The wizard has already finished".to_string()))?;
+                self.finish(ctx, WizardOutcome::Completed(state)).await?;
+            }
+            StepTransition::Goto(id) => {
+                let index = self
+                    .steps
+                    .iter()
+                    .position(|s| s.id == id)
+                    .ok_or_else(|| Error::Msg(format!("Wizard step {} not found", id)))?;
+                self.show_step(&ctx.http, index).await?;
+            }
+            StepTransition::Retry(reason) => {
+                let channel_id = ChannelId(self.message.channel_id);
+                EphemeralMessage::create(&ctx.http, channel_id, MEDIUM_TIMEOUT, |m| {
+                    m.content(reason)
+                })
+                .await?;
+            }
+            StepTransition::Cancel => self.finish(ctx, WizardOutcome::Cancelled).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the wizard with the given outcome, stops handling events
+    /// and removes the controls of the wizard
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn finish(&mut self, ctx: &Context, outcome: WizardOutcome<T>) -> Result<()> {
+        let completed = matches!(outcome, WizardOutcome::Completed(_));
+        self.resolve(outcome);
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.remove(&self.message);
+
+        self.close(&ctx.http, completed).await
+    }
+
+    /// Removes the controls from the message and shows
+    /// the completion page if the wizard was completed
+    async fn close(&mut self, http: &Http, completed: bool) -> Result<()> {
+        let mut message = json!({ "components": [] });
+
+        if let Some(page) = self.completion_page.as_ref().filter(|_| completed) {
+            let mut page = page.get().await?;
+            page.set_components(CreateComponents::default());
+            message = serde_json::to_value(page.0).unwrap();
+        }
+        http.edit_message(self.message.channel_id, self.message.message_id, &message)
+            .await?;
+
+        if !self.steps[self.current_step].reactions.is_empty() {
+            http.delete_message_reactions(self.message.channel_id, self.message.message_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the outcome to the handle of the wizard
+    fn resolve(&mut self, outcome: WizardOutcome<T>) {
+        self.finished = true;
+
+        if let Some(sender) = self.sender.take() {
+            if sender.send(outcome).is_err() {
+                tracing::debug!("Wizard handle was dropped before the wizard finished");
+            }
+        }
+    }
+
+    /// Returns if the given user is allowed to answer the wizard
+    fn is_permitted(&self, user_id: UserId) -> bool {
+        self.owner.is_none_or(|owner| owner == user_id)
+    }
+}
+
+#[async_trait]
+impl<T: Send + Sync + 'static> EventDrivenMessage for Wizard<T> {
+    fn is_frozen(&self) -> bool {
+        self.finished
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn update(&mut self, http: &Http) -> Result<()> {
+        if self.finished || Instant::now() < self.expires_at {
+            return Ok(());
+        }
+        tracing::debug!("Wizard timeout reached");
+        self.resolve(WizardOutcome::TimedOut);
+        self.close(http, false).await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_deleted(&mut self, _ctx: &Context) -> Result<()> {
+        self.resolve(WizardOutcome::MessageDeleted);
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_shutdown(&mut self, ctx: &Context) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.resolve(WizardOutcome::Shutdown);
+        self.close(&ctx.http, false).await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_reaction_add(&mut self, ctx: &Context, reaction: Reaction) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        let current_user = ctx.http.get_current_user().await?;
+        let user_id = reaction.user_id.ok_or(Error::NoCache)?;
+
+        if user_id == current_user.id {
+            return Ok(());
+        }
+        reaction.delete(ctx).await?;
+        let key = control_key(&reaction.emoji);
+        let is_input = self.steps[self.current_step]
+            .reactions
+            .iter()
+            .any(|r| control_key(r) == key);
+
+        if is_input && self.is_permitted(user_id) {
+            self.handle_input(ctx, WizardInput::Reaction(reaction.emoji))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_message_create(&mut self, ctx: &Context, message: Message) -> Result<()> {
+        if self.finished
+            || message.author.bot
+            || !self.steps[self.current_step].accepts_text
+            || !self.is_permitted(message.author.id)
+        {
+            return Ok(());
+        }
+        if self.delete_replies {
+            if let Err(e) = message.delete(ctx).await {
+                tracing::error!("Failed to delete wizard reply: {:?}", e);
+            }
+        }
+
+        self.handle_input(ctx, WizardInput::Message(Box::new(message)))
+            .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn on_interaction_create(
+        &mut self,
+        ctx: &Context,
+        mut interaction: MessageComponentInteraction,
+    ) -> Result<()> {
+        interaction.defer(&ctx.http).await?;
+
+        if self.finished || !self.is_permitted(interaction.user.id) {
+            return Ok(());
+        }
+        let custom_id = &interaction.data.custom_id;
+        let step = &self.steps[self.current_step];
+
+        let input = if custom_id == WIZARD_SELECT_ID && !step.select_options.is_empty() {
+            WizardInput::Select(mem::take(&mut interaction.data.values))
+        } else if step.buttons.iter().any(|(id, _, _)| id == custom_id) {
+            WizardInput::Button(custom_id.clone())
+        } else {
+            return Ok(());
+        };
+
+        self.handle_input(ctx, input).await
+    }
+}
+
+/// A handle to a running wizard
+pub struct WizardHandle<T> {
+    message: MessageHandle,
+    receiver: oneshot::Receiver<WizardOutcome<T>>,
+}
+
+impl<T> WizardHandle<T> {
+    /// Returns the handle of the wizard message
+    pub fn message(&self) -> MessageHandle {
+        self.message
+    }
+
+    /// Waits for the wizard to finish and returns its outcome.
+    /// Returns [WizardOutcome::Shutdown] if the wizard stopped without an outcome
+    pub async fn wait(self) -> WizardOutcome<T> {
+        self.receiver.await.unwrap_or(WizardOutcome::Shutdown)
+    }
+}
+
+/// A builder for wizards
+pub struct WizardBuilder<T> {
+    state: T,
+    steps: Vec<WizardStep<T>>,
+    owner: Option<UserId>,
+    timeout: Duration,
+    delete_replies: bool,
+    completion_page: Option<Page<'static>>,
+}
+
+impl<T: Send + Sync + 'static> WizardBuilder<T> {
+    /// Creates a new wizard builder with the initial state of the wizard
+    pub fn new(state: T) -> Self {
+        Self {
+            state,
+            steps: Vec::new(),
+            owner: None,
+            timeout: Duration::from_secs(120),
+            delete_replies: false,
+            completion_page: None,
+        }
+    }
+
+    /// Adds a step to the wizard. Steps are shown in the order they're added in
+    pub fn add_step(mut self, step: WizardStep<T>) -> Self {
+        self.steps.push(step);
+
+        self
+    }
+
+    /// Only accepts input from the given user
+    pub fn owner(mut self, user_id: UserId) -> Self {
+        self.owner = Some(user_id);
+
+        self
+    }
+
+    /// Sets the time the wizard waits for input on each step
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Deletes messages that were used as text input
+    pub fn delete_replies(mut self, value: bool) -> Self {
+        self.delete_replies = value;
+
+        self
+    }
+
+    /// Sets the page that is shown when the wizard was completed
+    pub fn completion_page(mut self, page: Page<'static>) -> Self {
+        self.completion_page = Some(page);
+
+        self
+    }
+
+    /// Creates the wizard in the given channel
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn build(self, ctx: &Context, channel_id: ChannelId) -> Result<WizardHandle<T>> {
+        let first_step = self
+            .steps
+            .first()
+            .ok_or_else(|| Error::Msg("A wizard needs at least one step".to_string()))?;
+        let mut page = first_step.prompt.get().await?;
+        page.set_components(first_step.create_components());
+        let reactions = first_step.reactions.clone();
+
+        let message = channel_id
+            .send_message(ctx, |msg| {
+                mem::swap(msg, &mut page);
+                msg
+            })
+            .await?;
+        let handle = MessageHandle::new(message.channel_id, message.id);
+        let (sender, receiver) = oneshot::channel();
+
+        let wizard = Wizard {
+            message: handle,
+            steps: self.steps,
+            current_step: 0,
+            state: Some(self.state),
+            owner: self.owner,
+            timeout: self.timeout,
+            expires_at: Instant::now() + self.timeout,
+            delete_replies: self.delete_replies,
+            completion_page: self.completion_page,
+            sender: Some(sender),
+            finished: false,
+        };
+        let listeners = get_listeners_from_context(ctx).await?;
+        listeners.insert(handle, Arc::new(Mutex::new(Box::new(wizard).into())));
+
+        for emoji in reactions {
+            message.react(ctx, emoji).await?;
+        }
+
+        Ok(WizardHandle {
+            message: handle,
+            receiver,
+        })
+    }
+}